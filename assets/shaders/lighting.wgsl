@@ -0,0 +1,46 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+@group(0) @binding(0)
+var t: texture_2d<f32>;
+@group(0) @binding(1)
+var ts: sampler;
+
+struct LightingSettings {
+    lamp_color: vec4<f32>,
+    flashlight_color: vec4<f32>,
+    lamp_position: vec2<f32>,
+    flashlight_position: vec2<f32>,
+    ambient: f32,
+    lamp_radius: f32,
+    flashlight_radius: f32,
+    _padding: f32,
+};
+@group(0) @binding(2)
+var<uniform> lighting: LightingSettings;
+
+// The camera always shows this many world units from top to bottom.
+const VERTICAL_RESOLUTION: f32 = 1080.0;
+
+// Full strength in the middle, fading out smoothly towards the radius.
+fn falloff(world: vec2<f32>, position: vec2<f32>, radius: f32) -> f32 {
+    if radius <= 0.0 {
+        return 0.0;
+    }
+    let d = saturate(distance(world, position) / radius);
+    return 1.0 - smoothstep(0.0, 1.0, d);
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let sample = textureSample(t, ts, in.uv);
+    let resolution = vec2<f32>(textureDimensions(t));
+    let size = vec2<f32>(VERTICAL_RESOLUTION * resolution.x / resolution.y, -VERTICAL_RESOLUTION);
+    let world = (in.uv - 0.5) * size;
+
+    let light = vec3<f32>(lighting.ambient)
+        + lighting.lamp_color.rgb * falloff(world, lighting.lamp_position, lighting.lamp_radius)
+        + lighting.flashlight_color.rgb
+            * falloff(world, lighting.flashlight_position, lighting.flashlight_radius);
+
+    return vec4<f32>(sample.rgb * min(light, vec3<f32>(1.2)), 1.0);
+}
@@ -1,7 +1,13 @@
 use crate::{
     components::UpdateSet,
+    detuning::{self, Detuning},
+    mixer::{ApplyMix, Mixer, RadioChannel, SfxChannel, Track},
+    morse,
     player::{Player, ResetUniverse},
+    progress::Flag,
+    radio_static::RadioStatic,
     right_speaker::RightSpeakerDestroyed,
+    solutions::{self, Solutions},
 };
 use bevy::prelude::*;
 use bevy_kira_audio::prelude::*;
@@ -10,8 +16,10 @@ use crate::gamedata::{AmRadioFreq, PresetAmRadioFreq};
 
 // Outer bandwidth defines when the channel starts to be heard
 // Inner bandwidth defines when the channel is at max volume
-const STATION_OUTER_BANDWIDTH_DELTA: i32 = 30;
-const STATION_INNER_BANDWIDTH_DELTA: i32 = 10;
+pub const STATION_OUTER_BANDWIDTH_DELTA: i32 = 30;
+pub const STATION_INNER_BANDWIDTH_DELTA: i32 = 10;
+// How far a station is pushed down when the others drown it out entirely.
+const CAPTURE: f64 = 0.5;
 
 #[derive(Component)]
 pub struct RadioStation {
@@ -23,176 +31,310 @@ pub struct RadioStation {
 #[derive(Component)]
 pub struct RadioAudio;
 
-#[derive(Resource)]
-#[allow(dead_code)]
-pub struct WhiteNoise(Handle<AudioInstance>);
+/// The word the morse station is keying.
+#[derive(Component)]
+struct MorseBroadcast(&'static str);
 
 impl RadioStation {
+    fn new(
+        radio: &AudioChannel<RadioChannel>,
+        source: Handle<AudioSource>,
+        frequency: PresetAmRadioFreq,
+    ) -> (Self, Detuning) {
+        let station = Self {
+            handle: radio.play(source.clone()).looped().paused().handle(),
+            frequency: frequency.into(),
+            playing: false,
+        };
+        (station, Detuning::new(source))
+    }
+
     fn freq_in_band(&self, freq: i32) -> bool {
         (freq - self.frequency.0).abs() <= STATION_OUTER_BANDWIDTH_DELTA
     }
+}
 
-    fn freq_to_volume(&self, freq: i32) -> f64 {
-        if !self.freq_in_band(freq) {
-            return 0.0;
-        }
+// How loud a station is with the dial `delta` kHz off it.
+fn band_volume(delta: i32) -> f64 {
+    let delta = delta.abs();
+    if delta > STATION_OUTER_BANDWIDTH_DELTA {
+        return 0.0;
+    }
+    if delta <= STATION_INNER_BANDWIDTH_DELTA {
+        return 1.0;
+    }
 
-        let delta = (freq - self.frequency.0).abs();
-        if delta <= STATION_INNER_BANDWIDTH_DELTA {
-            return 1.0;
-        }
+    let volume_sqrt = 1.0
+        - ((delta - STATION_INNER_BANDWIDTH_DELTA) as f64
+            / (STATION_OUTER_BANDWIDTH_DELTA - STATION_INNER_BANDWIDTH_DELTA) as f64);
+
+    volume_sqrt * volume_sqrt
+}
 
-        let volume_sqrt = 1.0
-            - ((delta - STATION_INNER_BANDWIDTH_DELTA) as f64
-                / (STATION_OUTER_BANDWIDTH_DELTA - STATION_INNER_BANDWIDTH_DELTA) as f64);
+// Signals adding up the way carriers do at the receiver, never past 1. They're
+// taken loudest first so that the order they come in can't change the result.
+fn combine(volumes: impl Iterator<Item = f64>) -> f64 {
+    let mut volumes: Vec<f64> = volumes.collect();
+    volumes.sort_by(|a, b| b.total_cmp(a));
+    1.0 - volumes
+        .iter()
+        .fold(1.0, |quiet, volume| quiet * (1.0 - volume))
+}
 
-        volume_sqrt * volume_sqrt
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StationReception {
+    /// What the station plays at once the others have had their share.
+    pub volume: f64,
+    /// How much of what's heard around the station comes from the others,
+    /// from 0 to 1.
+    pub interference: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reception {
+    /// One for each station, in the order they were given.
+    pub stations: Vec<StationReception>,
+    /// How strongly the band comes in overall, from 0 to 1.
+    pub signal: f64,
+}
+
+/// How every station at `stations` comes in with the dial at `freq`.
+/// Stations whose bands overlap are heard together, and each one loses some
+/// of its volume and clarity to the others.
+pub fn reception(freq: i32, stations: &[AmRadioFreq]) -> Reception {
+    let volumes: Vec<f64> = stations
+        .iter()
+        .map(|station| band_volume(freq - station.0))
+        .collect();
+    let stations = volumes
+        .iter()
+        .enumerate()
+        .map(|(i, &volume)| {
+            let others = combine(
+                volumes
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| j != i)
+                    .map(|(_, &other)| other),
+            );
+            let interference = if volume > 0.0 {
+                others / (volume + others)
+            } else {
+                0.0
+            };
+            StationReception {
+                // The stronger carrier captures the receiver and pushes the
+                // weaker one down.
+                volume: volume * (1.0 - CAPTURE * interference),
+                interference,
+            }
+        })
+        .collect();
+    Reception {
+        stations,
+        signal: combine(volumes.into_iter()),
+    }
+}
+
+// Every voice of every station, with the whistles and detuned copies.
+fn pan_stations(
+    radio_stations: &Query<(&RadioStation, &Detuning)>,
+    audio_instances: &mut Assets<AudioInstance>,
+    panning: f64,
+) {
+    for (radio_station, detuning) in radio_stations.iter() {
+        let voices = detuning.voices(&radio_station.handle, detuning::mix(0));
+        let whistle = detuning.whistle.iter().map(|whistle| (whistle, 0.0));
+        for (handle, _) in voices.into_iter().chain(whistle) {
+            if let Some(instance) = audio_instances.get_mut(handle) {
+                instance.set_panning(panning, AudioTween::default());
+            }
+        }
     }
 }
 
 fn on_right_speaker_destroyed(
-    audio: Res<Audio>,
+    sfx: Res<AudioChannel<SfxChannel>>,
     asset_server: Res<AssetServer>,
-    radio_stations: Query<&RadioStation>,
-    whitenoise: Res<WhiteNoise>,
+    radio_stations: Query<(&RadioStation, &Detuning)>,
     mut audio_instances: ResMut<Assets<AudioInstance>>,
     mut right_speaker_destroyed: EventReader<RightSpeakerDestroyed>,
 ) {
     for _ in right_speaker_destroyed.read() {
-        audio.play(asset_server.load("audio/explosion.ogg"));
-
-        for radio_station in radio_stations.iter() {
-            if let Some(instance) = audio_instances.get_mut(&radio_station.handle) {
-                instance.set_panning(0.0, AudioTween::default());
-            }
-        }
-        if let Some(instance) = audio_instances.get_mut(&whitenoise.0) {
-            instance.set_panning(0.0, AudioTween::default());
-        }
+        sfx.play(asset_server.load("audio/explosion.ogg"));
+        pan_stations(&radio_stations, &mut audio_instances, 0.0);
     }
 }
 
 fn on_reset_universe(
-    radio_stations: Query<&RadioStation>,
-    whitenoise: Res<WhiteNoise>,
+    radio_stations: Query<(&RadioStation, &Detuning)>,
     mut audio_instances: ResMut<Assets<AudioInstance>>,
     mut reset_universe: EventReader<ResetUniverse>,
 ) {
     for _ in reset_universe.read() {
-        for radio_station in radio_stations.iter() {
-            if let Some(instance) = audio_instances.get_mut(&radio_station.handle) {
-                instance.set_panning(0.5, AudioTween::default());
-            }
-        }
-        if let Some(instance) = audio_instances.get_mut(&whitenoise.0) {
-            instance.set_panning(0.5, AudioTween::default());
-        }
+        pan_stations(&radio_stations, &mut audio_instances, 0.5);
     }
 }
 
 fn update(
-    radio_freqs: Query<&AmRadioFreq, (Changed<AmRadioFreq>, With<RadioAudio>)>,
-    whitenoise: Res<WhiteNoise>,
-    mut radio_stations: Query<&mut RadioStation>,
+    radio_freqs: Query<Ref<AmRadioFreq>, With<RadioAudio>>,
+    mut radio_static: ResMut<RadioStatic>,
+    mut radio_stations: Query<(&mut RadioStation, Ref<Detuning>)>,
     mut audio_instances: ResMut<Assets<AudioInstance>>,
     time: Res<Time>,
     player: Res<Player>,
+    mixer: Res<Mixer>,
 ) {
-    if radio_freqs.is_empty() {
+    if !mixer.is_changed()
+        && !radio_freqs.iter().any(|freq| freq.is_changed())
+        && !radio_stations
+            .iter()
+            .any(|(_, detuning)| detuning.is_changed())
+    {
         return;
     }
 
-    let mut whitenoise_volume = 1.0;
+    let panning = if player.has(Flag::RightSpeakerBroken) {
+        0.0
+    } else {
+        0.5
+    };
+    let frequencies: Vec<AmRadioFreq> = radio_stations
+        .iter()
+        .map(|(radio_station, _)| radio_station.frequency)
+        .collect();
+    let mut signal: f64 = 0.0;
     for radio_freq in &radio_freqs {
-        for mut radio_station in &mut radio_stations {
-            if let Some(instance) = audio_instances.get_mut(&radio_station.handle) {
-                if radio_station.freq_in_band(radio_freq.0) {
-                    if !radio_station.playing {
+        let reception = reception(radio_freq.0, &frequencies);
+        signal = signal.max(reception.signal);
+        for ((mut radio_station, detuning), station) in
+            radio_stations.iter_mut().zip(&reception.stations)
+        {
+            let delta = radio_freq.0 - radio_station.frequency.0;
+            let detune_mix = detuning::mix(delta).interfered(station.interference);
+            let voices = detuning.voices(&radio_station.handle, detune_mix);
+            let whistle = detuning
+                .whistle
+                .iter()
+                .map(|whistle| (whistle, detuning::whistle_level(delta)));
+            // A paused radio is switched off, as if tuned away, and picks the
+            // broadcast up wherever the clock has got to when it comes back.
+            if radio_station.freq_in_band(radio_freq.0) && !mixer.track(Track::Radio).paused {
+                // Copies that just arrived have to catch up with the rest.
+                let restart = !radio_station.playing || detuning.is_changed();
+                for (handle, share) in voices.into_iter().chain(whistle) {
+                    let Some(instance) = audio_instances.get_mut(handle) else {
+                        continue;
+                    };
+                    if restart {
                         instance.seek_to(time.elapsed_seconds_f64());
                         instance.resume(AudioTween::default());
-                        radio_station.playing = true;
                     }
-
-                    let station_volume = radio_station.freq_to_volume(radio_freq.0);
-                    instance.set_volume(station_volume, AudioTween::default());
-
-                    if player.right_speaker_broken {
-                        instance.set_panning(0.0, AudioTween::default());
-                    } else {
-                        instance.set_panning(0.5, AudioTween::default());
+                    instance.set_volume(
+                        station.volume * share * mixer.gain(Track::Radio),
+                        AudioTween::default(),
+                    );
+                    instance.set_panning(panning, AudioTween::default());
+                }
+                if let Some(instance) = detuning
+                    .whistle
+                    .as_ref()
+                    .and_then(|whistle| audio_instances.get_mut(whistle))
+                {
+                    instance.set_playback_rate(
+                        detuning::whistle_playback_rate(delta),
+                        AudioTween::default(),
+                    );
+                }
+                radio_station.playing = true;
+            } else {
+                for (handle, _) in voices.into_iter().chain(whistle) {
+                    if let Some(instance) = audio_instances.get_mut(handle) {
+                        instance.pause(AudioTween::default());
                     }
-
-                    whitenoise_volume = 0.5 + 0.5 * (1.0 - station_volume);
-                } else {
-                    instance.pause(AudioTween::default());
-                    radio_station.playing = false;
                 }
+                radio_station.playing = false;
             }
         }
     }
-    if let Some(instance) = audio_instances.get_mut(&whitenoise.0) {
-        instance.set_volume(whitenoise_volume, AudioTween::default());
+    radio_static.signal = signal;
+}
 
-        if player.right_speaker_broken {
-            instance.set_panning(0.0, AudioTween::default());
-        } else {
-            instance.set_panning(0.5, AudioTween::default());
+// The morse station keys a different word whenever the solutions change or
+// the speaker blows. The new broadcast starts afresh, detuned copies and all.
+fn update_morse_broadcast(
+    player: Res<Player>,
+    solutions: Res<Solutions>,
+    radio: Res<AudioChannel<RadioChannel>>,
+    mut sources: ResMut<Assets<AudioSource>>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+    mut stations: Query<(&mut RadioStation, &mut Detuning, &mut MorseBroadcast)>,
+) {
+    if !player.is_changed() && !solutions.is_changed() {
+        return;
+    }
+    let word = solutions.morse_word(&player);
+    for (mut radio_station, mut detuning, mut broadcast) in &mut stations {
+        if broadcast.0 == word {
+            continue;
         }
+        for (handle, _) in detuning.voices(&radio_station.handle, detuning::mix(0)) {
+            if let Some(instance) = audio_instances.get_mut(handle) {
+                instance.stop(AudioTween::default());
+            }
+        }
+        let source = sources.add(morse::broadcast(word));
+        radio_station.handle = radio.play(source.clone()).looped().paused().handle();
+        radio_station.playing = false;
+        detuning.retune(source);
+        broadcast.0 = word;
     }
 }
 
-fn setup(mut commands: Commands, audio: Res<Audio>, asset_server: Res<AssetServer>) {
+fn setup(
+    mut commands: Commands,
+    radio: Res<AudioChannel<RadioChannel>>,
+    asset_server: Res<AssetServer>,
+    mut sources: ResMut<Assets<AudioSource>>,
+    player: Res<Player>,
+    solutions: Res<Solutions>,
+) {
     commands.spawn((AmRadioFreq(600), RadioAudio));
 
-    commands.insert_resource(WhiteNoise(
-        audio
-            .play(asset_server.load("audio/whitenoise.ogg"))
-            .looped()
-            .handle(),
+    let word = solutions.morse_word(&player);
+    commands.spawn((
+        RadioStation::new(
+            &radio,
+            sources.add(morse::broadcast(word)),
+            PresetAmRadioFreq::Morse,
+        ),
+        MorseBroadcast(word),
     ));
-    commands.spawn(RadioStation {
-        handle: audio
-            .play(asset_server.load("audio/morse-code.ogg"))
-            .looped()
-            .paused()
-            .handle(),
-        frequency: PresetAmRadioFreq::Morse.into(),
-        playing: false,
-    });
-    commands.spawn(RadioStation {
-        handle: audio
-            .play(asset_server.load("audio/number-station.ogg"))
-            .looped()
-            .paused()
-            .handle(),
-        frequency: PresetAmRadioFreq::Numbers.into(),
-        playing: false,
-    });
-    commands.spawn(RadioStation {
-        handle: audio
-            .play(asset_server.load("audio/song.ogg"))
-            .looped()
-            .paused()
-            .handle(),
-        frequency: PresetAmRadioFreq::Music.into(),
-        playing: false,
-    });
-    commands.spawn(RadioStation {
-        handle: audio
-            .play(asset_server.load("audio/news.ogg"))
-            .looped()
-            .paused()
-            .handle(),
-        frequency: PresetAmRadioFreq::News.into(),
-        playing: false,
-    });
+    for (path, frequency) in [
+        ("audio/number-station.ogg", PresetAmRadioFreq::Numbers),
+        ("audio/song.ogg", PresetAmRadioFreq::Music),
+        ("audio/news.ogg", PresetAmRadioFreq::News),
+    ] {
+        commands.spawn(RadioStation::new(
+            &radio,
+            asset_server.load(path),
+            frequency,
+        ));
+    }
 }
 
 pub fn plugin(app: &mut App) {
     app.add_plugins(AudioPlugin);
-    app.add_systems(Startup, setup);
+    // After the solutions, which the morse station sends.
+    app.add_systems(Startup, setup.after(solutions::setup));
     app.add_systems(
         Update,
-        (update, on_right_speaker_destroyed, on_reset_universe).in_set(UpdateSet::Scene),
+        (
+            on_right_speaker_destroyed,
+            on_reset_universe,
+            update_morse_broadcast,
+        )
+            .in_set(UpdateSet::Scene),
     );
+    app.add_systems(Update, update.in_set(ApplyMix));
 }
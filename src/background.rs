@@ -1,28 +1,59 @@
 use bevy::prelude::*;
 
-use crate::gamedata::AmRadioFreq;
-
-use std::fmt::Write;
+use crate::audio::RadioAudio;
+use crate::components::UpdateSet;
+use crate::gamedata::{AmRadioFreq, SceneId};
+use crate::player::{LoadScene, SceneItem};
 
 #[derive(Component)]
 struct FreqText;
 
-fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn freq_text(freq: &AmRadioFreq) -> String {
+    format!("{} kHz", freq.0)
+}
+
+fn load_scene(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    radio: Query<&AmRadioFreq, With<RadioAudio>>,
+    mut load_scene: EventReader<LoadScene>,
+) {
+    for load_scene in load_scene.read() {
+        if load_scene.0 != SceneId::Radio {
+            continue;
+        }
+        let value = radio.get_single().map(freq_text).unwrap_or_default();
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    value,
+                    TextStyle {
+                        font: asset_server.load("fonts/FiraMono-Regular.ttf"),
+                        font_size: 60.0,
+                        color: Color::BLACK,
+                    },
+                )
+                .with_justify(JustifyText::Center),
+                transform: Transform::from_xyz(285.0, 125.0, 4.0),
+                ..default()
+            },
+            FreqText,
+            SceneItem(SceneId::Radio),
+        ));
+    }
 }
 
 fn update(
-    player_radio_freqs: Query<&AmRadioFreq, Changed<AmRadioFreq>>,
+    player_radio_freqs: Query<&AmRadioFreq, (With<RadioAudio>, Changed<AmRadioFreq>)>,
     mut text: Query<&mut Text, With<FreqText>>,
 ) {
     for player_radio_freq in &player_radio_freqs {
         for mut text in &mut text {
-            text.sections[0].value.clear();
-            write!(text.sections[0].value, "{} kHz", player_radio_freq.0).unwrap();
+            text.sections[0].value = freq_text(player_radio_freq);
         }
     }
 }
 
 pub fn background_plugin(app: &mut App) {
-    app.add_systems(Startup, setup);
-    app.add_systems(Update, update);
+    app.add_systems(Update, (load_scene, update).in_set(UpdateSet::Scene));
 }
@@ -14,7 +14,12 @@ fn setup(mut commands: Commands) {
     let mut settings = crate::pixelate::PixelateSettings::default();
     settings.block_size = PIXELATE_BLOCK_SIZE;
 
-    commands.spawn((camera, MainCamera, settings));
+    commands.spawn((
+        camera,
+        MainCamera,
+        settings,
+        crate::lighting_pass::LightingSettings::default(),
+    ));
 }
 
 pub fn camera_plugin(app: &mut App) {
@@ -0,0 +1,534 @@
+//! Rows of letter wheels that open something once they spell out a code.
+//!
+//! A scene spawns a lock with [`CombinationLock::spawn`] and waits for
+//! [`LockSolved`]. Wheel positions live in the [`Player`], so they survive
+//! leaving and re-entering the scene.
+//!
+//! Clicking a wheel also focuses it for typing: a typed letter jumps the
+//! focused wheel to it and moves on to the next one, Backspace steps back and
+//! Escape lets go of the keyboard again.
+//!
+//! Locks click as they turn, complain when a wrong combination is left
+//! standing and clunk open. The sounds are synthesized at startup.
+
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+
+use crate::components::{ClickableArea, ClickableShape, Rectangle, UpdateSet};
+use crate::event_log::GameplayEvent;
+use crate::gamedata::{LetterPuzzle, SceneId};
+use crate::input::{typed_letter, MousePosition};
+use crate::mixer::UiChannel;
+use crate::player::{keyboard_input_system, Player, SceneItem};
+
+const JAMMED_ALPHA: f32 = 0.3;
+const FOCUSED_SCALE: f32 = 1.2;
+/// How long a wrong combination has to be left standing to count as tried.
+const SETTLE: Duration = Duration::from_millis(1500);
+
+const SAMPLE_RATE: u32 = 44_100;
+const AMPLITUDE: f32 = 0.3;
+// Short ramps at both ends of every strike, so that they don't click more
+// than they're meant to.
+const RAMP_SECONDS: f32 = 0.002;
+
+/// Sent once when a lock's wheels first spell out its solution.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LockSolved(pub LetterPuzzle);
+
+/// After `attempts` wrong combinations in a row the lock jams for
+/// `duration`.
+#[derive(Debug, Clone, Copy)]
+pub struct Lockout {
+    pub attempts: u32,
+    pub duration: Duration,
+}
+
+/// What a lock sounds like as it's turned, tried, opened and jammed.
+#[derive(Debug, Clone, Copy)]
+pub enum LockSounds {
+    /// Tumblers that click, rattle and clunk.
+    Mechanical,
+    /// A keypad that beeps, buzzes and chimes.
+    Electronic,
+}
+
+pub struct WheelButton {
+    pub center: Vec2,
+    pub size: Vec2,
+    /// How many letters a click turns the wheel by.
+    pub step: isize,
+}
+
+pub struct WheelLayout {
+    pub letter: Vec3,
+    pub buttons: Vec<WheelButton>,
+}
+
+impl WheelLayout {
+    pub fn up_down(letter: Vec3, up: Vec2, down: Vec2, size: Vec2) -> Self {
+        Self {
+            letter,
+            buttons: vec![
+                WheelButton {
+                    center: up,
+                    size,
+                    step: 1,
+                },
+                WheelButton {
+                    center: down,
+                    size,
+                    step: -1,
+                },
+            ],
+        }
+    }
+
+    /// Clicking the letter itself turns the wheel forwards.
+    pub fn click_to_advance(letter: Vec3, size: Vec2) -> Self {
+        Self {
+            letter,
+            buttons: vec![WheelButton {
+                center: letter.truncate(),
+                size,
+                step: 1,
+            }],
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct CombinationLock {
+    pub puzzle: LetterPuzzle,
+    pub alphabet: &'static [&'static str],
+    pub solution: &'static str,
+    pub lockout: Option<Lockout>,
+    pub sounds: LockSounds,
+}
+
+impl CombinationLock {
+    pub fn spells(&self, wheels: &[usize]) -> bool {
+        wheels.len() == self.solution.len()
+            && wheels
+                .iter()
+                .zip(self.solution.chars())
+                .all(|(wheel, c)| self.alphabet[*wheel].starts_with(c))
+    }
+
+    /// Spawns the lock with one wheel per entry in `layout`, showing the
+    /// player's current wheel positions.
+    pub fn spawn(
+        self,
+        commands: &mut Commands,
+        player: &Player,
+        scene: SceneId,
+        layout: &[WheelLayout],
+        style: TextStyle,
+    ) {
+        let wheels = player.letter_wheels(self.puzzle);
+        let state = LockState {
+            solved: self.spells(wheels),
+            ..default()
+        };
+        let letters: Vec<&str> = wheels.iter().map(|i| self.alphabet[*i]).collect();
+        let lock = commands.spawn((self, state, SceneItem(scene))).id();
+
+        for (index, wheel) in layout.iter().enumerate() {
+            commands.spawn((
+                Text2dBundle {
+                    text: Text::from_section(letters[index], style.clone())
+                        .with_justify(JustifyText::Center),
+                    transform: Transform::from_translation(wheel.letter),
+                    ..default()
+                },
+                LockWheel { lock, index },
+                SceneItem(scene),
+            ));
+            for button in &wheel.buttons {
+                commands.spawn((
+                    ClickableShape::Rectangle(Rectangle::from_pos_width_height(
+                        button.center,
+                        button.size.x,
+                        button.size.y,
+                    )),
+                    LockButton {
+                        lock,
+                        wheel: index,
+                        step: button.step,
+                    },
+                    SceneItem(scene),
+                ));
+            }
+        }
+    }
+}
+
+#[derive(Component, Default)]
+struct LockState {
+    solved: bool,
+    /// Counts down to a wrong attempt after the wheels were last turned.
+    settling: Option<Timer>,
+    wrong_attempts: u32,
+    jammed: Option<Timer>,
+    /// The wheel that typed letters go to.
+    focus: Option<usize>,
+}
+
+#[derive(Component)]
+struct LockWheel {
+    lock: Entity,
+    index: usize,
+}
+
+#[derive(Component)]
+struct LockButton {
+    lock: Entity,
+    wheel: usize,
+    step: isize,
+}
+
+struct ToneSet {
+    turn: Handle<AudioSource>,
+    wrong: Handle<AudioSource>,
+    solved: Handle<AudioSource>,
+    jammed: Handle<AudioSource>,
+}
+
+#[derive(Resource)]
+struct LockTones {
+    mechanical: ToneSet,
+    electronic: ToneSet,
+}
+
+impl LockTones {
+    fn of(&self, sounds: LockSounds) -> &ToneSet {
+        match sounds {
+            LockSounds::Mechanical => &self.mechanical,
+            LockSounds::Electronic => &self.electronic,
+        }
+    }
+}
+
+/// `frequencies` struck together `start` seconds in, dying away by `decay`
+/// per second.
+struct Strike {
+    start: f32,
+    frequencies: &'static [f32],
+    length: f32,
+    decay: f32,
+}
+
+fn synthesize(strikes: &[Strike]) -> AudioSource {
+    let to_frames = |seconds: f32| (seconds * SAMPLE_RATE as f32).round() as usize;
+    let ramp = to_frames(RAMP_SECONDS);
+    let end = strikes
+        .iter()
+        .map(|strike| to_frames(strike.start + strike.length))
+        .max()
+        .unwrap_or(0);
+    let mut samples = vec![0.0; end];
+    for strike in strikes {
+        let start = to_frames(strike.start);
+        let length = to_frames(strike.length);
+        for i in 0..length {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            let edge = (i.min(length - i) as f32 / ramp as f32).min(1.0);
+            let envelope = (-strike.decay * t).exp() * edge;
+            let sample: f32 = strike.frequencies.iter().map(|f| (TAU * f * t).sin()).sum();
+            samples[start + i] += sample * envelope * AMPLITUDE / strike.frequencies.len() as f32;
+        }
+    }
+
+    AudioSource {
+        sound: StaticSoundData {
+            sample_rate: SAMPLE_RATE,
+            frames: samples.into_iter().map(Frame::from_mono).collect(),
+            settings: StaticSoundSettings::default(),
+        },
+    }
+}
+
+/// `count` quick, dull strikes `gap` seconds apart.
+fn rattle(count: usize, gap: f32) -> Vec<Strike> {
+    (0..count)
+        .map(|i| Strike {
+            start: i as f32 * gap,
+            frequencies: &[700.0, 1100.0],
+            length: 0.04,
+            decay: 60.0,
+        })
+        .collect()
+}
+
+/// `count` harsh tones `gap` seconds apart.
+fn buzz(count: usize, gap: f32) -> Vec<Strike> {
+    (0..count)
+        .map(|i| Strike {
+            start: i as f32 * gap,
+            frequencies: &[180.0, 360.0, 540.0, 720.0],
+            length: 0.3,
+            decay: 0.0,
+        })
+        .collect()
+}
+
+fn setup(mut commands: Commands, mut sources: ResMut<Assets<AudioSource>>) {
+    let mechanical = ToneSet {
+        turn: sources.add(synthesize(&[Strike {
+            start: 0.0,
+            frequencies: &[2400.0, 3700.0],
+            length: 0.03,
+            decay: 120.0,
+        }])),
+        wrong: sources.add(synthesize(&rattle(3, 0.05))),
+        solved: sources.add(synthesize(&[
+            Strike {
+                start: 0.0,
+                frequencies: &[1800.0],
+                length: 0.02,
+                decay: 150.0,
+            },
+            Strike {
+                start: 0.0,
+                frequencies: &[90.0, 135.0],
+                length: 0.3,
+                decay: 12.0,
+            },
+        ])),
+        jammed: sources.add(synthesize(&rattle(8, 0.04))),
+    };
+    let electronic = ToneSet {
+        turn: sources.add(synthesize(&[Strike {
+            start: 0.0,
+            frequencies: &[1200.0],
+            length: 0.06,
+            decay: 0.0,
+        }])),
+        wrong: sources.add(synthesize(&buzz(1, 0.0))),
+        solved: sources.add(synthesize(&[
+            Strike {
+                start: 0.0,
+                frequencies: &[1047.0],
+                length: 0.15,
+                decay: 4.0,
+            },
+            Strike {
+                start: 0.12,
+                frequencies: &[1319.0],
+                length: 0.15,
+                decay: 4.0,
+            },
+            Strike {
+                start: 0.24,
+                frequencies: &[1568.0],
+                length: 0.5,
+                decay: 4.0,
+            },
+        ])),
+        jammed: sources.add(synthesize(&buzz(3, 0.4))),
+    };
+    commands.insert_resource(LockTones {
+        mechanical,
+        electronic,
+    });
+}
+
+#[derive(SystemParam)]
+struct LockFeedback<'w> {
+    audio: Res<'w, AudioChannel<UiChannel>>,
+    tones: Res<'w, LockTones>,
+    lock_solved: EventWriter<'w, LockSolved>,
+    gameplay_events: EventWriter<'w, GameplayEvent>,
+}
+
+impl LockFeedback<'_> {
+    /// Reports that `wheel` was turned, and whether that opened the lock.
+    fn turned(
+        &mut self,
+        lock: &CombinationLock,
+        state: &mut LockState,
+        wheels: &[usize],
+        wheel: usize,
+    ) {
+        self.gameplay_events
+            .send(GameplayEvent::PuzzleLetterChanged {
+                puzzle: lock.puzzle,
+                position: wheel,
+                letter: lock.alphabet[wheels[wheel]],
+            });
+
+        if lock.spells(wheels) {
+            state.solved = true;
+            state.settling = None;
+            state.focus = None;
+            self.lock_solved.send(LockSolved(lock.puzzle));
+            self.audio.play(self.tones.of(lock.sounds).solved.clone());
+        } else {
+            state.settling = Some(Timer::new(SETTLE, TimerMode::Once));
+            self.audio.play(self.tones.of(lock.sounds).turn.clone());
+        }
+    }
+}
+
+fn turn_wheels(
+    mut player: ResMut<Player>,
+    mouse_pos: Res<MousePosition>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    buttons: Query<(&ClickableShape, &LockButton)>,
+    mut locks: Query<(&CombinationLock, &mut LockState)>,
+    mut feedback: LockFeedback,
+) {
+    if !mouse_button.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    // Clicking anywhere else lets go of the keyboard.
+    for (_, mut state) in locks.iter_mut() {
+        state.focus = None;
+    }
+
+    for (shape, button) in buttons.iter() {
+        if !shape.contains(mouse_pos.0) {
+            continue;
+        }
+        let Ok((lock, mut state)) = locks.get_mut(button.lock) else {
+            continue;
+        };
+        if state.solved || state.jammed.is_some() {
+            continue;
+        }
+
+        let wheels = player.letter_wheels_mut(lock.puzzle);
+        let letters = lock.alphabet.len() as isize;
+        wheels[button.wheel] =
+            (wheels[button.wheel] as isize + button.step).rem_euclid(letters) as usize;
+        state.focus = Some(button.wheel);
+        feedback.turned(lock, &mut state, wheels, button.wheel);
+    }
+}
+
+// Runs before scene navigation so that typing into a lock doesn't also walk
+// out of the scene.
+fn type_letters(
+    mut keyboard: ResMut<ButtonInput<KeyCode>>,
+    mut player: ResMut<Player>,
+    mut locks: Query<(&CombinationLock, &mut LockState)>,
+    mut feedback: LockFeedback,
+) {
+    for (lock, mut state) in locks.iter_mut() {
+        let keys: Vec<KeyCode> = keyboard.get_just_pressed().copied().collect();
+        for key in keys {
+            let Some(focus) = state.focus else {
+                break;
+            };
+            let wheels = player.letter_wheels_mut(lock.puzzle);
+            match key {
+                KeyCode::Escape => state.focus = None,
+                KeyCode::Backspace => state.focus = Some(focus.saturating_sub(1)),
+                _ => {
+                    let Some(letter) = typed_letter(key) else {
+                        continue;
+                    };
+                    let position = lock.alphabet.iter().position(|l| l.starts_with(letter));
+                    if let Some(position) = position.filter(|_| state.jammed.is_none()) {
+                        wheels[focus] = position;
+                        state.focus = Some((focus + 1).min(wheels.len() - 1));
+                        feedback.turned(lock, &mut state, wheels, focus);
+                    }
+                }
+            }
+            keyboard.clear_just_pressed(key);
+        }
+    }
+}
+
+fn count_attempts(
+    time: Res<Time>,
+    audio: Res<AudioChannel<UiChannel>>,
+    tones: Res<LockTones>,
+    mut locks: Query<(&CombinationLock, &mut LockState)>,
+) {
+    for (lock, mut state) in locks.iter_mut() {
+        if let Some(jammed) = &mut state.jammed {
+            if jammed.tick(time.delta()).finished() {
+                state.jammed = None;
+            }
+            continue;
+        }
+        let Some(settling) = &mut state.settling else {
+            continue;
+        };
+        if !settling.tick(time.delta()).finished() {
+            continue;
+        }
+
+        state.settling = None;
+        let tones = tones.of(lock.sounds);
+        let Some(lockout) = lock.lockout else {
+            audio.play(tones.wrong.clone());
+            continue;
+        };
+        state.wrong_attempts += 1;
+        if state.wrong_attempts >= lockout.attempts {
+            state.wrong_attempts = 0;
+            state.jammed = Some(Timer::new(lockout.duration, TimerMode::Once));
+            audio.play(tones.jammed.clone());
+        } else {
+            audio.play(tones.wrong.clone());
+        }
+    }
+}
+
+fn update_wheels(
+    player: Res<Player>,
+    locks: Query<(&CombinationLock, &LockState)>,
+    mut wheels: Query<(&LockWheel, &mut Text, &mut Transform)>,
+) {
+    for (wheel, mut text, mut transform) in wheels.iter_mut() {
+        let Ok((lock, state)) = locks.get(wheel.lock) else {
+            continue;
+        };
+        let letter = lock.alphabet[player.letter_wheels(lock.puzzle)[wheel.index]];
+        let alpha = if state.jammed.is_some() {
+            JAMMED_ALPHA
+        } else {
+            1.0
+        };
+        let scale = if state.focus == Some(wheel.index) {
+            FOCUSED_SCALE
+        } else {
+            1.0
+        };
+        if transform.scale.x != scale {
+            transform.scale = Vec3::splat(scale);
+        }
+
+        let section = &text.sections[0];
+        if section.value != letter || section.style.color.alpha() != alpha {
+            let section = &mut text.sections[0];
+            section.value = letter.to_string();
+            section.style.color.set_alpha(alpha);
+        }
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_event::<LockSolved>();
+    app.add_systems(Startup, setup);
+    app.add_systems(
+        Update,
+        (turn_wheels, count_attempts, update_wheels)
+            .chain()
+            .in_set(UpdateSet::Scene),
+    );
+    app.add_systems(
+        Update,
+        type_letters
+            .before(keyboard_input_system)
+            .in_set(UpdateSet::PreScene),
+    );
+}
@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 use enum_dispatch::enum_dispatch;
+use rand::{rngs::StdRng, SeedableRng};
 
 use crate::gamedata::SceneId;
 
@@ -21,6 +22,23 @@ impl UpdateSet {
 
 pub type Keyboard<'a> = Res<'a, ButtonInput<KeyCode>>;
 
+/// The only source of randomness gameplay code should use, so that a seeded
+/// session plays out the same way every time.
+#[derive(Resource)]
+pub struct GameRng(pub StdRng);
+
+impl GameRng {
+    pub fn seeded(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed))
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self(StdRng::from_entropy())
+    }
+}
+
 #[enum_dispatch]
 pub trait ClickableArea {
     fn contains(&self, pos: Vec2) -> bool;
@@ -0,0 +1,300 @@
+//! What tuning off a station sounds like: the audio narrows and muffles, a
+//! whistle beats against it, and towards the edge of the band it breaks up.
+//!
+//! Sounds can't be filtered as they play, so each station gets a muffled and a
+//! distorted copy made once it has loaded. The copies play in step with the
+//! station and are faded between as the dial moves.
+
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use bevy::tasks::{block_on, poll_once, AsyncComputeTaskPool, Task};
+use bevy_kira_audio::prelude::*;
+
+use crate::audio::STATION_OUTER_BANDWIDTH_DELTA;
+use crate::mixer::RadioChannel;
+
+// The copies only keep what the filters let through, so they make do with a
+// lower sample rate and a quarter of the memory.
+const COPY_SAMPLE_RATE: u32 = 11_025;
+const MUFFLED_CUTOFF: f32 = 1200.0;
+const DISTORTED_LOW_CUT: f32 = 400.0;
+const DISTORTED_HIGH_CUT: f32 = 2500.0;
+const OVERDRIVE: f32 = 8.0;
+const DISTORTED_LEVEL: f32 = 0.6;
+
+// Where across the band, from the centre to the edge, the station stops
+// sounding clear and starts breaking up.
+const CLEAR_UNTIL: f64 = 0.5;
+const DISTORTED_FROM: f64 = 0.6;
+// How much of a station breaks up when the others drown it out entirely.
+const INTERFERENCE_DISTORTION: f64 = 0.6;
+
+const WHISTLE_SAMPLE_RATE: u32 = 44_100;
+// A second of it holds a whole number of cycles, so it loops cleanly.
+const WHISTLE_HZ: f32 = 1000.0;
+const WHISTLE_HZ_PER_KHZ: f64 = 150.0;
+const WHISTLE_LEVEL: f64 = 0.15;
+// Right on the frequency the whistle drops to nothing, the way a carrier
+// zero-beats.
+const ZERO_BEAT_KHZ: f64 = 3.0;
+
+/// The copies of a station that play alongside it while it's detuned.
+#[derive(Component)]
+pub struct Detuning {
+    source: Handle<AudioSource>,
+    pub whistle: Option<Handle<AudioInstance>>,
+    pub muffled: Option<Handle<AudioInstance>>,
+    pub distorted: Option<Handle<AudioInstance>>,
+}
+
+impl Detuning {
+    pub fn new(source: Handle<AudioSource>) -> Self {
+        Self {
+            source,
+            whistle: None,
+            muffled: None,
+            distorted: None,
+        }
+    }
+
+    /// Starts over from a new sound. The old copies have to be stopped first.
+    pub fn retune(&mut self, source: Handle<AudioSource>) {
+        self.source = source;
+        self.muffled = None;
+        self.distorted = None;
+    }
+
+    /// The station's sound and its copies, each with its share of the volume
+    /// in `mix`. Copies that aren't made yet leave their share to the clear
+    /// sound.
+    pub fn voices<'a>(
+        &'a self,
+        clear: &'a Handle<AudioInstance>,
+        mix: DetuneMix,
+    ) -> Vec<(&'a Handle<AudioInstance>, f64)> {
+        let mut voices = vec![(clear, mix.clear)];
+        for (copy, share) in [
+            (&self.muffled, mix.muffled),
+            (&self.distorted, mix.distorted),
+        ] {
+            match copy {
+                Some(copy) => voices.push((copy, share)),
+                None => voices[0].1 += share,
+            }
+        }
+        voices
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DetuneMix {
+    pub clear: f64,
+    pub muffled: f64,
+    pub distorted: f64,
+}
+
+impl DetuneMix {
+    /// The mix with another station bleeding over it, which breaks it up the
+    /// way being off the edge of its band does.
+    pub fn interfered(self, interference: f64) -> Self {
+        let moved = INTERFERENCE_DISTORTION * interference.clamp(0.0, 1.0);
+        Self {
+            clear: self.clear * (1.0 - moved),
+            muffled: self.muffled * (1.0 - moved),
+            distorted: self.distorted + (self.clear + self.muffled) * moved,
+        }
+    }
+}
+
+/// How a station `delta` kHz off is split between its clear, muffled and
+/// distorted sound. The shares add up to one.
+pub fn mix(delta: i32) -> DetuneMix {
+    let t = (delta.abs() as f64 / STATION_OUTER_BANDWIDTH_DELTA as f64).min(1.0);
+    let clear = (1.0 - t / CLEAR_UNTIL).max(0.0);
+    let distorted = ((t - DISTORTED_FROM) / (1.0 - DISTORTED_FROM)).clamp(0.0, 1.0);
+    DetuneMix {
+        clear,
+        muffled: 1.0 - clear - distorted,
+        distorted,
+    }
+}
+
+/// How loud the whistle is `delta` kHz off a station, relative to it.
+pub fn whistle_level(delta: i32) -> f64 {
+    WHISTLE_LEVEL * (delta.abs() as f64 / ZERO_BEAT_KHZ).min(1.0)
+}
+
+/// The playback rate that puts the whistle at the pitch for `delta`.
+pub fn whistle_playback_rate(delta: i32) -> f64 {
+    delta.abs() as f64 * WHISTLE_HZ_PER_KHZ / WHISTLE_HZ as f64
+}
+
+/// Made on another thread, since songs take a while to get through.
+#[derive(Component)]
+struct PendingCopies {
+    /// What the copies are of, in case the station has moved on to another
+    /// sound since.
+    source: Handle<AudioSource>,
+    task: Task<(AudioSource, AudioSource)>,
+}
+
+#[derive(Resource)]
+struct WhistleSource(Handle<AudioSource>);
+
+struct LowPass {
+    coefficient: f32,
+    state: Frame,
+}
+
+impl LowPass {
+    fn new(cutoff: f32, sample_rate: u32) -> Self {
+        Self {
+            coefficient: 1.0 - (-TAU * cutoff / sample_rate as f32).exp(),
+            state: Frame::ZERO,
+        }
+    }
+
+    fn process(&mut self, input: Frame) -> Frame {
+        self.state += (input - self.state) * self.coefficient;
+        self.state
+    }
+}
+
+// Runs every frame through `process`, keeping only what the lower sample
+// rate needs.
+fn resample(sound: &StaticSoundData, mut process: impl FnMut(Frame) -> Frame) -> StaticSoundData {
+    let step = (sound.sample_rate / COPY_SAMPLE_RATE).max(1);
+    let mut frames = Vec::with_capacity(sound.frames.len() / step as usize + 1);
+    for (i, frame) in sound.frames.iter().enumerate() {
+        let processed = process(*frame);
+        if i % step as usize == 0 {
+            frames.push(processed);
+        }
+    }
+    StaticSoundData {
+        sample_rate: sound.sample_rate / step,
+        frames: frames.into(),
+        settings: StaticSoundSettings::default(),
+    }
+}
+
+/// The station with the highs gone, as if heard through a narrow filter.
+pub fn muffle(sound: &StaticSoundData) -> StaticSoundData {
+    let mut low_passes = [
+        LowPass::new(MUFFLED_CUTOFF, sound.sample_rate),
+        LowPass::new(MUFFLED_CUTOFF, sound.sample_rate),
+    ];
+    resample(sound, |frame| {
+        low_passes
+            .iter_mut()
+            .fold(frame, |frame, low_pass| low_pass.process(frame))
+    })
+}
+
+/// The station overdriven and thinned out, as it sounds at the band's edge.
+pub fn distort(sound: &StaticSoundData) -> StaticSoundData {
+    let mut rumble = LowPass::new(DISTORTED_LOW_CUT, sound.sample_rate);
+    let mut low_passes = [
+        LowPass::new(DISTORTED_HIGH_CUT, sound.sample_rate),
+        LowPass::new(DISTORTED_HIGH_CUT, sound.sample_rate),
+    ];
+    resample(sound, |frame| {
+        let thin = frame - rumble.process(frame);
+        let driven = Frame::new(
+            (thin.left * OVERDRIVE).tanh(),
+            (thin.right * OVERDRIVE).tanh(),
+        ) * DISTORTED_LEVEL;
+        low_passes
+            .iter_mut()
+            .fold(driven, |frame, low_pass| low_pass.process(frame))
+    })
+}
+
+/// A second of a sine wave at [`WHISTLE_HZ`].
+pub fn whistle() -> StaticSoundData {
+    let frames = (0..WHISTLE_SAMPLE_RATE)
+        .map(|i| {
+            let t = i as f32 / WHISTLE_SAMPLE_RATE as f32;
+            Frame::from_mono((TAU * WHISTLE_HZ * t).sin())
+        })
+        .collect();
+    StaticSoundData {
+        sample_rate: WHISTLE_SAMPLE_RATE,
+        frames,
+        settings: StaticSoundSettings::default(),
+    }
+}
+
+fn setup(mut commands: Commands, mut sources: ResMut<Assets<AudioSource>>) {
+    let whistle = sources.add(AudioSource { sound: whistle() });
+    commands.insert_resource(WhistleSource(whistle));
+}
+
+fn prepare(
+    mut commands: Commands,
+    sources: Res<Assets<AudioSource>>,
+    whistle: Res<WhistleSource>,
+    radio: Res<AudioChannel<RadioChannel>>,
+    mut stations: Query<(Entity, &mut Detuning), Without<PendingCopies>>,
+) {
+    for (entity, mut detuning) in stations.iter_mut() {
+        if detuning.whistle.is_none() {
+            detuning.whistle = Some(radio.play(whistle.0.clone()).looped().paused().handle());
+        }
+        if detuning.muffled.is_some() {
+            continue;
+        }
+        let Some(source) = sources.get(&detuning.source) else {
+            continue;
+        };
+        let sound = source.sound.clone();
+        let task = AsyncComputeTaskPool::get().spawn(async move {
+            (
+                AudioSource {
+                    sound: muffle(&sound),
+                },
+                AudioSource {
+                    sound: distort(&sound),
+                },
+            )
+        });
+        commands.entity(entity).insert(PendingCopies {
+            source: detuning.source.clone(),
+            task,
+        });
+    }
+}
+
+// The copies start out paused. Their arrival counts as a change to the
+// station, which lines them up with it if it's already playing.
+fn finish(
+    mut commands: Commands,
+    mut sources: ResMut<Assets<AudioSource>>,
+    radio: Res<AudioChannel<RadioChannel>>,
+    mut stations: Query<(Entity, &mut PendingCopies, &mut Detuning)>,
+) {
+    for (entity, mut pending, mut detuning) in stations.iter_mut() {
+        if pending.source != detuning.source {
+            commands.entity(entity).remove::<PendingCopies>();
+            continue;
+        }
+        let Some((muffled, distorted)) = block_on(poll_once(&mut pending.task)) else {
+            continue;
+        };
+        detuning.muffled = Some(radio.play(sources.add(muffled)).looped().paused().handle());
+        detuning.distorted = Some(
+            radio
+                .play(sources.add(distorted))
+                .looped()
+                .paused()
+                .handle(),
+        );
+        commands.entity(entity).remove::<PendingCopies>();
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(Startup, setup);
+    app.add_systems(Update, (prepare, finish));
+}
@@ -0,0 +1,357 @@
+//! The ways a run can end, the screen that plays when one is reached, and the
+//! gallery of every ending the player has ever reached, kept between
+//! sessions.
+
+use std::time::Duration;
+
+use bevy::asset::ron;
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::camera::{HORIZONTAL_RESOLUTION, VERTICAL_RESOLUTION};
+use crate::components::{Keyboard, UpdateSet};
+use crate::event_log::GameplayEvent;
+use crate::game_state::GameState;
+use crate::mixer::MusicChannel;
+use crate::player::Player;
+use crate::progress::{Flag, Flags};
+use crate::settings;
+
+const GALLERY_KEY: &str = "endings.ron";
+const MUSIC: &str = "audio/song.ogg";
+const MUSIC_FADE: Duration = Duration::from_secs(2);
+
+const FADE_IN: Duration = Duration::from_millis(1500);
+/// Between the title and each line of the epilogue after it.
+const LINE_INTERVAL: Duration = Duration::from_secs(2);
+const Z: f32 = 20.0;
+const TEXT_COLOR: Color = Color::srgb(0.85, 0.85, 0.8);
+const UNREACHED: &str = "???";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Ending {
+    /// Told the switchboard to keep this universe.
+    Saved,
+    /// Pulled the save switch behind the wall.
+    Switched,
+    /// Told the switchboard to let this universe go.
+    Doomed,
+    /// Swapped places with the other side.
+    Traded,
+    /// Walked out of the front door.
+    Escaped,
+}
+
+/// In the order the gallery lists them.
+pub const ALL_ENDINGS: [Ending; 5] = [
+    Ending::Saved,
+    Ending::Switched,
+    Ending::Doomed,
+    Ending::Traded,
+    Ending::Escaped,
+];
+
+impl Ending {
+    pub const fn flag(self) -> Flag {
+        match self {
+            Self::Saved => Flag::UniverseSaved,
+            Self::Switched => Flag::SaveSwitchPulled,
+            Self::Doomed => Flag::UniverseAbandoned,
+            Self::Traded => Flag::UniverseTraded,
+            Self::Escaped => Flag::Escaped,
+        }
+    }
+
+    /// The ending `flags` have reached, if any.
+    pub fn reached(flags: &Flags) -> Option<Self> {
+        ALL_ENDINGS
+            .iter()
+            .copied()
+            .find(|ending| flags.contains(ending.flag()))
+    }
+
+    pub const fn title(self) -> &'static str {
+        match self {
+            Self::Saved => "UNIVERSE SAVED",
+            Self::Switched => "SWITCH PULLED",
+            Self::Doomed => "DOOMED",
+            Self::Traded => "TRADED",
+            Self::Escaped => "ESCAPED",
+        }
+    }
+
+    const fn epilogue(self) -> &'static [&'static str] {
+        match self {
+            Self::Saved => &[
+                "THE SWITCHBOARD KEEPS THIS ONE.",
+                "THE LINES GO QUIET.",
+                "FOR NOW, EVERYTHING STAYS.",
+            ],
+            Self::Switched => &[
+                "THE SWITCH CLICKS INTO PLACE.",
+                "THIS UNIVERSE WON'T RESET AGAIN.",
+                "WHATEVER HAPPENS NEXT, HAPPENS HERE.",
+            ],
+            Self::Doomed => &[
+                "THE SWITCHBOARD LETS IT GO.",
+                "THE RADIO FADES TO STATIC.",
+                "SO DOES EVERYTHING ELSE.",
+            ],
+            Self::Traded => &[
+                "SOMEONE ON THE OTHER SIDE SAYS THANK YOU.",
+                "YOUR ROOM IS THEIRS NOW.",
+                "THEIRS IS YOURS.",
+            ],
+            Self::Escaped => &[
+                "YOU STEPPED OUT INTO THE DARK.",
+                "THE KNOCKING STOPS.",
+                "NOBODY IS THERE.",
+            ],
+        }
+    }
+}
+
+/// Every ending reached so far, in the order they were first reached.
+#[derive(Resource, Debug, Default)]
+pub struct Gallery {
+    /// Where the gallery is kept, alongside the settings. Without a key it
+    /// only lasts the session.
+    pub key: Option<String>,
+    pub reached: Vec<Ending>,
+}
+
+impl Gallery {
+    fn at(key: &str) -> Self {
+        Self {
+            key: Some(key.to_string()),
+            reached: vec![],
+        }
+    }
+
+    pub fn load(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(key) = &self.key else {
+            return Ok(());
+        };
+        if let Some(text) = settings::read(key)? {
+            self.reached = ron::from_str(&text)?;
+        }
+        Ok(())
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(key) = &self.key {
+            settings::write(key, &ron::to_string(&self.reached)?)?;
+        }
+        Ok(())
+    }
+
+    /// Returns whether `ending` is new to the gallery.
+    pub fn record(&mut self, ending: Ending) -> bool {
+        if self.reached.contains(&ending) {
+            return false;
+        }
+        self.reached.push(ending);
+        true
+    }
+
+    /// One line per ending, with the ones not reached yet hidden.
+    fn listing(&self) -> String {
+        let names: Vec<&str> = ALL_ENDINGS
+            .iter()
+            .map(|ending| {
+                if self.reached.contains(ending) {
+                    ending.title()
+                } else {
+                    UNREACHED
+                }
+            })
+            .collect();
+        format!(
+            "ENDINGS FOUND {}/{}\n{}",
+            self.reached.len(),
+            ALL_ENDINGS.len(),
+            names.join("   ")
+        )
+    }
+}
+
+struct Showing {
+    ending: Ending,
+    elapsed: Duration,
+    music: Handle<AudioInstance>,
+    dismissed: bool,
+}
+
+impl Showing {
+    /// Everything is on screen once the whole epilogue is.
+    fn finished(&self) -> bool {
+        self.elapsed >= FADE_IN + LINE_INTERVAL * (self.ending.epilogue().len() as u32 + 1)
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct EndingScreen {
+    showing: Option<Showing>,
+}
+
+impl EndingScreen {
+    #[cfg(test)]
+    pub fn ending(&self) -> Option<Ending> {
+        self.showing.as_ref().map(|showing| showing.ending)
+    }
+}
+
+#[derive(Component)]
+struct Backdrop;
+
+/// Each shows up once the screen has been up for this long.
+#[derive(Component)]
+struct Reveal(Duration);
+
+fn load_gallery(mut gallery: ResMut<Gallery>) {
+    if let Err(err) = gallery.load() {
+        error!("failed to load the endings gallery: {}", err);
+    }
+}
+
+fn start(
+    mut commands: Commands,
+    player: Res<Player>,
+    audio: Res<AudioChannel<MusicChannel>>,
+    asset_server: Res<AssetServer>,
+    mut screen: ResMut<EndingScreen>,
+    mut gallery: ResMut<Gallery>,
+    mut gameplay_events: EventWriter<GameplayEvent>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(ending) = Ending::reached(&player.flags) else {
+        return;
+    };
+
+    gameplay_events.send(GameplayEvent::EndingReached(ending));
+    if gallery.record(ending) {
+        if let Err(err) = gallery.save() {
+            error!("failed to save the endings gallery: {}", err);
+        }
+    }
+    let music = audio
+        .play(asset_server.load(MUSIC))
+        .fade_in(AudioTween::linear(MUSIC_FADE))
+        .looped()
+        .handle();
+    screen.showing = Some(Showing {
+        ending,
+        elapsed: Duration::ZERO,
+        music,
+        dismissed: false,
+    });
+    next_state.set(GameState::Ending);
+
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::BLACK.with_alpha(0.0),
+                custom_size: Some(Vec2::new(HORIZONTAL_RESOLUTION, VERTICAL_RESOLUTION)),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 0.0, Z),
+            ..default()
+        },
+        Backdrop,
+        StateScoped(GameState::Ending),
+    ));
+
+    let font = asset_server.load("fonts/FiraMono-Regular.ttf");
+    let style = |font_size| TextStyle {
+        font: font.clone(),
+        font_size,
+        color: TEXT_COLOR,
+    };
+    let mut text = |value: String, font_size, y, at| {
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(value, style(font_size)).with_justify(JustifyText::Center),
+                transform: Transform::from_xyz(0.0, y, Z + 1.0),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            Reveal(at),
+            StateScoped(GameState::Ending),
+        ));
+    };
+    text(ending.title().to_string(), 90.0, 250.0, FADE_IN);
+    let epilogue = ending.epilogue();
+    for (index, line) in epilogue.iter().enumerate() {
+        let at = FADE_IN + LINE_INTERVAL * (index as u32 + 1);
+        text(line.to_string(), 45.0, 80.0 - 70.0 * index as f32, at);
+    }
+    let done = FADE_IN + LINE_INTERVAL * (epilogue.len() as u32 + 1);
+    text(gallery.listing(), 35.0, -250.0, done);
+    text("CLICK TO CONTINUE".to_string(), 35.0, -420.0, done);
+}
+
+// A click or key press once it's all shown moves on. The game clock is
+// stopped behind the ending, so it goes by real time.
+fn play(
+    time: Res<Time<Real>>,
+    keyboard: Keyboard,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    mut screen: ResMut<EndingScreen>,
+    mut backdrop: Query<&mut Sprite, With<Backdrop>>,
+    mut reveals: Query<(&Reveal, &mut Visibility)>,
+) {
+    let Some(showing) = &mut screen.showing else {
+        return;
+    };
+    showing.elapsed += time.delta();
+    let pressed = mouse_button.get_just_pressed().next().is_some()
+        || keyboard.get_just_pressed().next().is_some();
+    if pressed && showing.finished() {
+        showing.dismissed = true;
+    }
+
+    let alpha = (showing.elapsed.as_secs_f32() / FADE_IN.as_secs_f32()).min(1.0);
+    for mut sprite in backdrop.iter_mut() {
+        sprite.color.set_alpha(alpha);
+    }
+    for (Reveal(at), mut visibility) in reveals.iter_mut() {
+        if showing.elapsed >= *at && *visibility != Visibility::Inherited {
+            *visibility = Visibility::Inherited;
+        }
+    }
+}
+
+// Back to the main menu, where the run that ended can't be continued.
+fn finish(
+    mut screen: ResMut<EndingScreen>,
+    mut instances: ResMut<Assets<AudioInstance>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !screen
+        .showing
+        .as_ref()
+        .is_some_and(|showing| showing.dismissed)
+    {
+        return;
+    }
+    let Some(showing) = screen.showing.take() else {
+        return;
+    };
+
+    if let Some(instance) = instances.get_mut(&showing.music) {
+        instance.stop(AudioTween::linear(MUSIC_FADE));
+    }
+    next_state.set(GameState::MainMenu);
+}
+
+pub fn plugin(app: &mut App) {
+    app.insert_resource(Gallery::at(GALLERY_KEY));
+    app.init_resource::<EndingScreen>();
+    app.add_systems(Startup, load_gallery);
+    app.add_systems(Update, start.in_set(UpdateSet::PostScene));
+    app.add_systems(
+        Update,
+        (play, finish).chain().run_if(in_state(GameState::Ending)),
+    );
+}
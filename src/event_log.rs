@@ -0,0 +1,251 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+use bevy::core::FrameCount;
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+
+use crate::camera::{HORIZONTAL_RESOLUTION, VERTICAL_RESOLUTION};
+use crate::components::{Keyboard, UpdateSet};
+use crate::endings::Ending;
+use crate::gamedata::{debug_text_style, Item, LetterPuzzle, RenderLayer, SceneId};
+use crate::hints::Goal;
+use crate::player::{LightbulbColor, LoadScene, ResetUniverse};
+use crate::right_speaker::RightSpeakerDestroyed;
+use crate::tv::TvPlayerKilled;
+
+const EXPORT_KEY: KeyCode = KeyCode::F9;
+const EXPORT_PATH: &str = "gameplay_log.jsonl";
+const DEBUG_OVERLAY_LINES: usize = 12;
+
+/// Every meaningful change to the game's progress. Scene systems send these
+/// directly; the ones that mirror an existing event are forwarded here.
+#[derive(Event, Debug, Clone, PartialEq)]
+pub enum GameplayEvent {
+    ItemPickedUp(Item),
+    ItemUsed(Item),
+    PuzzleLetterChanged {
+        puzzle: LetterPuzzle,
+        position: usize,
+        letter: &'static str,
+    },
+    PhoneDigitDialed(usize),
+    CallConnected(&'static str),
+    DialogueChoice {
+        callee: &'static str,
+        digit: usize,
+    },
+    SceneEntered(SceneId),
+    SpeakerDestroyed,
+    DoorKnocked,
+    TvWon(LightbulbColor),
+    TvLost,
+    UniverseReset,
+    EndingReached(Ending),
+    HintShown {
+        goal: Goal,
+        tier: usize,
+    },
+}
+
+impl GameplayEvent {
+    pub const fn name(&self) -> &'static str {
+        use GameplayEvent::*;
+        match self {
+            ItemPickedUp(_) => "item_picked_up",
+            ItemUsed(_) => "item_used",
+            PuzzleLetterChanged { .. } => "puzzle_letter_changed",
+            PhoneDigitDialed(_) => "phone_digit_dialed",
+            CallConnected(_) => "call_connected",
+            DialogueChoice { .. } => "dialogue_choice",
+            SceneEntered(_) => "scene_entered",
+            SpeakerDestroyed => "speaker_destroyed",
+            DoorKnocked => "door_knocked",
+            TvWon(_) => "tv_won",
+            TvLost => "tv_lost",
+            UniverseReset => "universe_reset",
+            EndingReached(_) => "ending_reached",
+            HintShown { .. } => "hint_shown",
+        }
+    }
+
+    // All payloads are enum names, static letters or numbers, so none of
+    // them need escaping.
+    fn write_json_fields(&self, out: &mut String) -> std::fmt::Result {
+        use GameplayEvent::*;
+        match self {
+            ItemPickedUp(item) | ItemUsed(item) => write!(out, r#","item":"{:?}""#, item),
+            PuzzleLetterChanged {
+                puzzle,
+                position,
+                letter,
+            } => write!(
+                out,
+                r#","puzzle":"{:?}","position":{},"letter":"{}""#,
+                puzzle, position, letter
+            ),
+            PhoneDigitDialed(digit) => write!(out, r#","digit":{}"#, digit),
+            CallConnected(callee) => write!(out, r#","callee":"{}""#, callee),
+            DialogueChoice { callee, digit } => {
+                write!(out, r#","callee":"{}","digit":{}"#, callee, digit)
+            }
+            SceneEntered(scene) => write!(out, r#","scene":"{:?}""#, scene),
+            TvWon(color) => write!(out, r#","lightbulb":"{:?}""#, color),
+            EndingReached(ending) => write!(out, r#","ending":"{:?}""#, ending),
+            HintShown { goal, tier } => write!(out, r#","goal":"{:?}","tier":{}"#, goal, tier),
+            SpeakerDestroyed | DoorKnocked | TvLost | UniverseReset => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LoggedEvent {
+    pub frame: u32,
+    pub elapsed: Duration,
+    pub event: GameplayEvent,
+}
+
+impl LoggedEvent {
+    pub fn to_json_line(&self) -> String {
+        let mut line = String::new();
+        write!(
+            &mut line,
+            r#"{{"frame":{},"time":{:.3},"event":"{}""#,
+            self.frame,
+            self.elapsed.as_secs_f64(),
+            self.event.name()
+        )
+        .unwrap();
+        self.event.write_json_fields(&mut line).unwrap();
+        line.push('}');
+        line
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct GameplayLog {
+    pub entries: Vec<LoggedEvent>,
+}
+
+impl GameplayLog {
+    pub fn to_json_lines(&self) -> String {
+        self.entries
+            .iter()
+            .map(|entry| entry.to_json_line() + "\n")
+            .collect()
+    }
+}
+
+#[derive(Component)]
+struct DebugLogText;
+
+fn forward_events(
+    mut load_scene: EventReader<LoadScene>,
+    mut reset_universe: EventReader<ResetUniverse>,
+    mut right_speaker_destroyed: EventReader<RightSpeakerDestroyed>,
+    mut tv_player_killed: EventReader<TvPlayerKilled>,
+    mut gameplay_events: EventWriter<GameplayEvent>,
+) {
+    for LoadScene(scene) in load_scene.read() {
+        gameplay_events.send(GameplayEvent::SceneEntered(*scene));
+    }
+    for _ in reset_universe.read() {
+        gameplay_events.send(GameplayEvent::UniverseReset);
+    }
+    for _ in right_speaker_destroyed.read() {
+        gameplay_events.send(GameplayEvent::SpeakerDestroyed);
+    }
+    for _ in tv_player_killed.read() {
+        gameplay_events.send(GameplayEvent::TvLost);
+    }
+}
+
+fn record_events(
+    mut log: ResMut<GameplayLog>,
+    mut gameplay_events: EventReader<GameplayEvent>,
+    frame_count: Res<FrameCount>,
+    time: Res<Time>,
+) {
+    for event in gameplay_events.read() {
+        log.entries.push(LoggedEvent {
+            frame: frame_count.0,
+            elapsed: time.elapsed(),
+            event: event.clone(),
+        });
+    }
+}
+
+fn export(keyboard: Keyboard, log: Res<GameplayLog>) {
+    if !keyboard.just_pressed(EXPORT_KEY) {
+        return;
+    }
+
+    let lines = log.to_json_lines();
+    if cfg!(target_arch = "wasm32") {
+        // No filesystem on the web; the browser console is the export.
+        for line in lines.lines() {
+            info!("{}", line);
+        }
+    } else {
+        match std::fs::write(EXPORT_PATH, lines) {
+            Ok(()) => info!("exported {} events to {}", log.entries.len(), EXPORT_PATH),
+            Err(err) => error!("failed to export gameplay log: {}", err),
+        }
+    }
+}
+
+fn debug_update(log: Res<GameplayLog>, mut debug_text: Query<&mut Text, With<DebugLogText>>) {
+    if !log.is_changed() {
+        return;
+    }
+
+    for mut text in &mut debug_text {
+        text.sections[1].value.clear();
+        let skip = log.entries.len().saturating_sub(DEBUG_OVERLAY_LINES);
+        for entry in log.entries.iter().skip(skip) {
+            writeln!(
+                &mut text.sections[1].value,
+                "{:>8.2} {:?}",
+                entry.elapsed.as_secs_f32(),
+                entry.event
+            )
+            .unwrap();
+        }
+    }
+}
+
+fn debug_setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let style = debug_text_style(&asset_server);
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_sections([
+                TextSection::new("gameplay log:\n", style.clone()),
+                TextSection::from_style(style.clone()),
+            ])
+            .with_justify(JustifyText::Left),
+            text_anchor: Anchor::TopLeft,
+            transform: Transform::from_translation(Vec3::new(
+                -HORIZONTAL_RESOLUTION / 2.0,
+                VERTICAL_RESOLUTION / 2.0 * 8.5 / 10.0,
+                RenderLayer::DebugText.z(),
+            )),
+            ..default()
+        },
+        DebugLogText,
+    ));
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_event::<GameplayEvent>();
+    app.init_resource::<GameplayLog>();
+    app.add_systems(
+        Update,
+        (forward_events, record_events, export)
+            .chain()
+            .in_set(UpdateSet::PostScene),
+    );
+    if cfg!(feature = "debug_log") {
+        app.add_systems(Startup, debug_setup);
+        app.add_systems(Update, (debug_update).in_set(UpdateSet::Debug));
+    }
+}
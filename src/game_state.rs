@@ -0,0 +1,105 @@
+//! The top-level states the app moves through. Gameplay only runs while
+//! [`GameState::Playing`]; everything else is drawn over a frozen room.
+
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+
+use crate::components::UpdateSet;
+use crate::gamedata::SceneId;
+use crate::mixer::{Mixer, Track, ALL_TRACKS};
+use crate::replay::ReplayMode;
+
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum GameState {
+    /// Waiting for what the main menu and first scene need.
+    #[default]
+    Loading,
+    MainMenu,
+    Playing,
+    Paused,
+    /// Opened from the main menu or the pause menu, and goes back to it.
+    Settings,
+    /// An ending is on screen.
+    Ending,
+}
+
+#[derive(Resource)]
+struct Preloaded(Vec<UntypedHandle>);
+
+fn preload(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(Preloaded(vec![
+        asset_server
+            .load::<Font>("fonts/FiraMono-Regular.ttf")
+            .untyped(),
+        asset_server
+            .load::<Image>(SceneId::Desk.asset_path())
+            .untyped(),
+    ]));
+}
+
+// Recordings start playing straight away, so replays line up with them.
+fn skip_menu(mode: Res<ReplayMode>, mut next_state: ResMut<NextState<GameState>>) {
+    if !matches!(*mode, ReplayMode::Off) {
+        next_state.set(GameState::Playing);
+    }
+}
+
+fn finish_loading(
+    asset_server: Res<AssetServer>,
+    preloaded: Res<Preloaded>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    // A missing asset shouldn't keep the game from starting.
+    let ready = preloaded.0.iter().all(|handle| {
+        asset_server.is_loaded_with_dependencies(handle)
+            || matches!(asset_server.load_state(handle), LoadState::Failed(_))
+    });
+    if ready {
+        next_state.set(GameState::MainMenu);
+    }
+}
+
+// The room goes quiet along with the clock. The ending music is left to fade
+// out over the menu.
+fn pause_room_audio(mixer: &mut Mixer, paused: bool) {
+    for track in ALL_TRACKS {
+        if track != Track::Music {
+            mixer.track_mut(track).paused = paused;
+        }
+    }
+}
+
+fn pause_audio(mut mixer: ResMut<Mixer>) {
+    pause_room_audio(&mut mixer, true);
+}
+
+// Gameplay that goes by the clock rather than by frames, like the phone's call
+// schedule, has to stop with it.
+fn stop_clock(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn start_clock(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+fn resume_audio(mut mixer: ResMut<Mixer>) {
+    pause_room_audio(&mut mixer, false);
+}
+
+pub fn plugin(app: &mut App) {
+    app.init_state::<GameState>();
+    app.enable_state_scoped_entities::<GameState>();
+    app.configure_sets(
+        Update,
+        (UpdateSet::PreScene, UpdateSet::Scene, UpdateSet::PostScene)
+            .run_if(in_state(GameState::Playing)),
+    );
+    app.add_systems(Startup, (preload, skip_menu));
+    app.add_systems(Update, finish_loading.run_if(in_state(GameState::Loading)));
+    app.add_systems(OnEnter(GameState::Loading), pause_audio);
+    app.add_systems(OnEnter(GameState::Paused), (pause_audio, stop_clock));
+    app.add_systems(OnEnter(GameState::MainMenu), (pause_audio, stop_clock));
+    app.add_systems(OnEnter(GameState::Ending), (pause_audio, stop_clock));
+    app.add_systems(OnEnter(GameState::Playing), (resume_audio, start_clock));
+}
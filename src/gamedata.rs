@@ -1,5 +1,7 @@
 use bevy::prelude::*;
 
+use crate::player::LightbulbColor;
+
 #[derive(Debug, Clone, Copy)]
 pub enum RenderLayer {
     Background,
@@ -66,7 +68,7 @@ macro_rules! scene_path {
     };
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
 pub enum SceneId {
     Desk,
     Radio,
@@ -82,6 +84,7 @@ pub enum SceneId {
     Door,
     Behind,
     Phone,
+    Peephole,
 }
 
 impl SceneId {
@@ -114,6 +117,7 @@ impl SceneId {
         let scene = match self {
             Radio | Tv | Lamp | KeypadDrawer | KeypadDrawerSolved | KeypadDrawerEmpty
             | LockDrawer | LockDrawerEmpty | LockDrawerSolved | Phone => Desk,
+            Peephole => Door,
             _ => return None,
         };
         Some(scene)
@@ -136,6 +140,8 @@ impl SceneId {
             Door => scene_path!("door.png"),
             Behind => scene_path!("restart_universe_button.png"),
             Phone => scene_path!("phone.png"),
+            // The view through the peephole is drawn over the door.
+            Peephole => scene_path!("door.png"),
         }
     }
 }
@@ -148,9 +154,41 @@ const MAIN_RING_SCENES: [SceneId; 4] = [
     SceneId::BulletinBoard,
 ];
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Item {
+    Key,
+    SurgeProtector,
+    RadioModule,
+    Flashlight,
+    Lightbulb(LightbulbColor),
+}
+
+/// The letters on every letter wheel, in the order they turn through.
+pub const PUZZLE_CHARACTERS: [&str; 12] =
+    ["A", "B", "D", "E", "H", "I", "N", "O", "S", "R", "P", "U"];
+
+/// The digits on the door lock's wheels, one for each number of knocks.
+pub const DOOR_DIGITS: [&str; 5] = ["1", "2", "3", "4", "5"];
+
+/// The digit printed on each phone button, only readable in red light.
+pub const PHONE_BUTTON_LABELS: [usize; 9] = [7, 5, 3, 2, 4, 6, 9, 1, 8];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LetterPuzzle {
+    KeypadDrawer,
+    Behind,
+    Door,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
 pub struct AmRadioFreq(pub i32);
 
+impl AmRadioFreq {
+    /// The ends of the AM band, in kHz.
+    pub const MIN: i32 = 530;
+    pub const MAX: i32 = 1700;
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PresetAmRadioFreq {
     Morse,
@@ -0,0 +1,359 @@
+//! Tiered hints for whatever the player should be doing next, from a vague
+//! nudge to the explicit answer. The more explicit tiers only unlock after the
+//! player has gone a while without making progress.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::camera::{HORIZONTAL_RESOLUTION, VERTICAL_RESOLUTION};
+use crate::components::{
+    ClickableArea, ClickableLabel, ClickableShape, Keyboard, Rectangle, UpdateSet,
+};
+use crate::event_log::GameplayEvent;
+use crate::gamedata::{highlight_text_style, RenderLayer, SceneId, PHONE_BUTTON_LABELS};
+use crate::input::MousePosition;
+use crate::player::{LoadScene, Player};
+use crate::progress::{Flag, Flags, ENDINGS};
+use crate::solutions::Solutions;
+
+const HINT_KEY: KeyCode = KeyCode::KeyH;
+const HINT_DISPLAY_TIME: Duration = Duration::from_secs(8);
+const HINT_FONT_SIZE: f32 = 40.0;
+const BUTTON_POS: Vec2 = Vec2::new(
+    HORIZONTAL_RESOLUTION / 2.0 - 60.0,
+    VERTICAL_RESOLUTION / 2.0 - 60.0,
+);
+const BUTTON_IDLE_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.4);
+const BUTTON_LIT_COLOR: Color = Color::linear_rgb(0.0, 1.0, 1.0);
+
+/// How long the player has to go without progress before each tier unlocks.
+pub const TIER_DELAYS: [Duration; 3] = [
+    Duration::ZERO,
+    Duration::from_secs(45),
+    Duration::from_secs(120),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    FindKey,
+    OpenKeyDrawer,
+    TakeModule,
+    OpenKeypadDrawer,
+    TakeSurgeProtector,
+    ResetUniverse,
+    InstallSurgeProtector,
+    InstallModule,
+    WinTv,
+    InstallLightbulb,
+    DialPhone,
+}
+
+// In the order the intended solution reaches them.
+const GOALS: [Goal; 11] = [
+    Goal::FindKey,
+    Goal::OpenKeyDrawer,
+    Goal::TakeModule,
+    Goal::OpenKeypadDrawer,
+    Goal::TakeSurgeProtector,
+    Goal::ResetUniverse,
+    Goal::InstallSurgeProtector,
+    Goal::InstallModule,
+    Goal::WinTv,
+    Goal::InstallLightbulb,
+    Goal::DialPhone,
+];
+
+impl Goal {
+    /// The first goal not reached yet, or `None` once the game has ended.
+    pub fn current(flags: &Flags) -> Option<Self> {
+        if flags.contains_any(ENDINGS) {
+            return None;
+        }
+        GOALS.iter().copied().find(|goal| !goal.is_done(flags))
+    }
+
+    fn is_done(self, flags: &Flags) -> bool {
+        use Goal::*;
+        match self {
+            FindKey => flags.contains(Flag::HasKey),
+            OpenKeyDrawer => flags.contains(Flag::OpenedKeyDrawer),
+            TakeModule => flags.contains(Flag::HasMorseCodeTranslator),
+            OpenKeypadDrawer => flags.contains(Flag::OpenedKeypadDrawer),
+            TakeSurgeProtector => flags.contains(Flag::HasSurgeProtector),
+            ResetUniverse => !flags.contains(Flag::RightSpeakerBroken),
+            InstallSurgeProtector => flags.contains(Flag::InstalledSurgeProtector),
+            InstallModule => flags.contains(Flag::InstalledMorseCodeTranslator),
+            WinTv => flags.contains_any(&[Flag::RedBulbUnlocked, Flag::RedBulbInstalled]),
+            InstallLightbulb => flags.contains_all(&[Flag::RedBulbInstalled, Flag::LampOn]),
+            DialPhone => flags.contains(Flag::UniverseSaved),
+        }
+    }
+
+    /// Where the goal is reached, and what to call that place in a hint.
+    pub const fn place(self) -> (SceneId, &'static str) {
+        use Goal::*;
+        match self {
+            FindKey | InstallSurgeProtector => (SceneId::BulletinBoard, "bulletin board"),
+            OpenKeyDrawer => (SceneId::LockDrawer, "top drawer"),
+            TakeModule => (SceneId::LockDrawerSolved, "top drawer"),
+            OpenKeypadDrawer | InstallModule => (SceneId::Radio, "radio"),
+            TakeSurgeProtector => (SceneId::KeypadDrawerSolved, "bottom drawer"),
+            ResetUniverse => (SceneId::Behind, "wall behind you"),
+            WinTv => (SceneId::Tv, "TV"),
+            InstallLightbulb => (SceneId::Lamp, "lamp"),
+            DialPhone => (SceneId::Phone, "phone"),
+        }
+    }
+
+    /// From a vague nudge to the explicit answer.
+    pub fn hints(self, solutions: &Solutions) -> [String; 3] {
+        let phone_number: String = solutions
+            .phone_number()
+            .iter()
+            .map(|button| PHONE_BUTTON_LABELS[*button].to_string())
+            .collect();
+        self.templates().map(|hint| {
+            hint.replace("{keypad_code}", solutions.keypad_code)
+                .replace("{phone_number}", &phone_number)
+        })
+    }
+
+    const fn templates(self) -> [&'static str; 3] {
+        use Goal::*;
+        match self {
+            FindKey => [
+                "Drawers are usually locked for a reason.",
+                "Look around the room. Something small is pinned up somewhere.",
+                "The key is on the bulletin board, left of the desk (press A).",
+            ],
+            OpenKeyDrawer => [
+                "You have a key. What does it open?",
+                "One of the desk drawers has a keyhole.",
+                "Click the lock on the top desk drawer.",
+            ],
+            TakeModule => [
+                "The top drawer isn't empty.",
+                "There's a radio part in the top drawer.",
+                "Open the top drawer and take the radio module.",
+            ],
+            OpenKeypadDrawer => [
+                "The bottom drawer wants a word. Something in this room spells things out.",
+                "The radio module translates the morse station, whatever it costs.",
+                "Install the module and tune to the morse preset. The drawer code is {keypad_code}.",
+            ],
+            TakeSurgeProtector => [
+                "The bottom drawer isn't empty.",
+                "Whatever is in the bottom drawer protects against something.",
+                "Take the surge protector from the bottom drawer.",
+            ],
+            ResetUniverse => [
+                "That speaker isn't coming back. Not in this universe, anyway.",
+                "Things can start over. Turn around.",
+                "Press S at the desk and push the button to reset. You keep what you learned.",
+            ],
+            InstallSurgeProtector => [
+                "Radios don't like power spikes.",
+                "There's an outlet under the bulletin board.",
+                "Plug the surge protector in below the bulletin board, then install the module.",
+            ],
+            InstallModule => [
+                "The radio is safe to modify now.",
+                "The module fits the empty slot on the radio.",
+                "Install the module in the radio and listen to the morse preset.",
+            ],
+            WinTv => [
+                "The TV is more than a screen.",
+                "Get away from the monster on the TV. The edges of the screen wrap around.",
+                "On the TV, go up, then out through the right, bottom and left edges.",
+            ],
+            InstallLightbulb => [
+                "You won something on the TV.",
+                "The lamp could use a new bulb.",
+                "At the lamp, click under the shade to put in the red bulb, then pull the chain.",
+            ],
+            DialPhone => [
+                "The phone only works in the right light. Someone on the radio reads numbers.",
+                "The numbers station has the digits. The bulletin board has the order.",
+                "Pick up the phone, dial {phone_number} and press 1.",
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hint {
+    pub goal: Goal,
+    pub tier: usize,
+    pub text: String,
+}
+
+#[derive(Resource, Default)]
+pub struct Hints {
+    visited: HashSet<SceneId>,
+    goal: Option<Goal>,
+    last_progress: Duration,
+    /// How many tiers of the current goal's hints have been shown.
+    shown: usize,
+}
+
+impl Hints {
+    pub fn unlocked_tiers(&self, now: Duration) -> usize {
+        let stuck_for = now.saturating_sub(self.last_progress);
+        TIER_DELAYS
+            .iter()
+            .filter(|delay| stuck_for >= **delay)
+            .count()
+    }
+
+    /// Whether asking again would show something more explicit.
+    pub fn has_new_tier(&self, now: Duration) -> bool {
+        self.goal.is_some() && self.unlocked_tiers(now) > self.shown.max(1)
+    }
+
+    /// The next tier for the current goal, or the most explicit one unlocked
+    /// so far if the next one is still locked.
+    pub fn request(&mut self, now: Duration, solutions: &Solutions) -> Option<Hint> {
+        let goal = self.goal?;
+        let tier = self.shown.min(self.unlocked_tiers(now) - 1);
+        self.shown = self.shown.max(tier + 1);
+
+        let (scene, place) = goal.place();
+        let text = if tier == 0 && !self.visited.contains(&scene) {
+            format!("You haven't looked at the {} yet.", place)
+        } else {
+            goal.hints(solutions)[tier].clone()
+        };
+        Some(Hint { goal, tier, text })
+    }
+}
+
+#[derive(Component)]
+struct HintText(Timer);
+
+#[derive(Component)]
+struct HintButton;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let style = TextStyle {
+        font_size: HINT_FONT_SIZE,
+        ..highlight_text_style(&asset_server)
+    };
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section("", style.clone()).with_justify(JustifyText::Center),
+            transform: Transform::from_xyz(
+                0.0,
+                -VERTICAL_RESOLUTION / 2.0 + 80.0,
+                RenderLayer::HighlightText.z(),
+            ),
+            ..default()
+        },
+        HintText(Timer::new(HINT_DISPLAY_TIME, TimerMode::Once)),
+    ));
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "?",
+                TextStyle {
+                    font_size: 80.0,
+                    color: BUTTON_IDLE_COLOR,
+                    ..style
+                },
+            ),
+            transform: Transform::from_translation(
+                BUTTON_POS.extend(RenderLayer::HighlightText.z()),
+            ),
+            ..default()
+        },
+        ClickableShape::Rectangle(Rectangle::from_pos_width_height(BUTTON_POS, 80.0, 80.0)),
+        ClickableLabel("Hint"),
+        HintButton,
+    ));
+}
+
+fn track_progress(
+    mut hints: ResMut<Hints>,
+    player: Res<Player>,
+    time: Res<Time>,
+    mut load_scene: EventReader<LoadScene>,
+) {
+    for LoadScene(scene) in load_scene.read() {
+        hints.visited.insert(*scene);
+    }
+
+    let goal = Goal::current(&player.flags);
+    if goal != hints.goal {
+        hints.goal = goal;
+        hints.last_progress = time.elapsed();
+        hints.shown = 0;
+    }
+}
+
+fn show_hint(
+    mut hints: ResMut<Hints>,
+    solutions: Res<Solutions>,
+    time: Res<Time>,
+    keyboard: Keyboard,
+    mouse_pos: Res<MousePosition>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    button: Query<&ClickableShape, With<HintButton>>,
+    mut text: Query<(&mut Text, &mut HintText)>,
+    mut gameplay_events: EventWriter<GameplayEvent>,
+) {
+    let clicked = mouse_button.just_pressed(MouseButton::Left)
+        && button.iter().any(|shape| shape.contains(mouse_pos.0));
+    if !keyboard.just_pressed(HINT_KEY) && !clicked {
+        return;
+    }
+
+    let Some(hint) = hints.request(time.elapsed(), &solutions) else {
+        return;
+    };
+    gameplay_events.send(GameplayEvent::HintShown {
+        goal: hint.goal,
+        tier: hint.tier,
+    });
+    for (mut text, mut hint_text) in &mut text {
+        text.sections[0].value.clone_from(&hint.text);
+        hint_text.0.reset();
+    }
+}
+
+fn hide_hint(time: Res<Time>, mut text: Query<(&mut Text, &mut HintText)>) {
+    for (mut text, mut hint_text) in &mut text {
+        if hint_text.0.tick(time.delta()).just_finished() {
+            text.sections[0].value.clear();
+        }
+    }
+}
+
+// The button lights up whenever a more explicit hint is waiting.
+fn update_button(
+    hints: Res<Hints>,
+    time: Res<Time>,
+    mut button: Query<&mut Text, With<HintButton>>,
+) {
+    let color = if hints.has_new_tier(time.elapsed()) {
+        BUTTON_LIT_COLOR
+    } else {
+        BUTTON_IDLE_COLOR
+    };
+    for mut text in &mut button {
+        if text.sections[0].style.color != color {
+            text.sections[0].style.color = color;
+        }
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<Hints>();
+    app.add_systems(Startup, setup);
+    app.add_systems(
+        Update,
+        (track_progress, show_hint, hide_hint, update_button)
+            .chain()
+            .in_set(UpdateSet::PostScene),
+    );
+}
@@ -16,6 +16,40 @@ use crate::player::Player;
 #[derive(Resource, Default)]
 pub struct MousePosition(pub Vec2);
 
+pub const LETTER_KEYS: [KeyCode; 26] = {
+    use KeyCode::*;
+    [
+        KeyA, KeyB, KeyC, KeyD, KeyE, KeyF, KeyG, KeyH, KeyI, KeyJ, KeyK, KeyL, KeyM, KeyN, KeyO,
+        KeyP, KeyQ, KeyR, KeyS, KeyT, KeyU, KeyV, KeyW, KeyX, KeyY, KeyZ,
+    ]
+};
+pub const DIGIT_KEYS: [KeyCode; 10] = {
+    use KeyCode::*;
+    [
+        Digit0, Digit1, Digit2, Digit3, Digit4, Digit5, Digit6, Digit7, Digit8, Digit9,
+    ]
+};
+const NUMPAD_KEYS: [KeyCode; 10] = {
+    use KeyCode::*;
+    [
+        Numpad0, Numpad1, Numpad2, Numpad3, Numpad4, Numpad5, Numpad6, Numpad7, Numpad8, Numpad9,
+    ]
+};
+
+/// The capital letter printed on `key` on a US layout.
+pub fn typed_letter(key: KeyCode) -> Option<char> {
+    let index = LETTER_KEYS.iter().position(|k| *k == key)?;
+    Some((b'A' + index as u8) as char)
+}
+
+/// The digit on `key`, from either the number row or the numpad.
+pub fn typed_digit(key: KeyCode) -> Option<usize> {
+    DIGIT_KEYS
+        .iter()
+        .position(|k| *k == key)
+        .or_else(|| NUMPAD_KEYS.iter().position(|k| *k == key))
+}
+
 #[derive(Component)]
 struct DebugKeyInputText;
 
@@ -25,13 +59,17 @@ struct DebugCursorPosText;
 #[derive(Component)]
 struct CursorText;
 
-fn input_update(
+pub fn input_update(
     q_window: Query<&Window, With<PrimaryWindow>>,
     q_camera: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
     mut coords: ResMut<MousePosition>,
 ) {
-    let (camera, camera_transform) = q_camera.single();
-    let window = q_window.single();
+    // Headless apps have neither; whoever drives them sets the position directly.
+    let (Ok((camera, camera_transform)), Ok(window)) =
+        (q_camera.get_single(), q_window.get_single())
+    else {
+        return;
+    };
 
     if let Some(world_position) = window
         .cursor_position()
@@ -0,0 +1,152 @@
+//! How brightly each part of the screen is lit: the room is dark until the
+//! lamp is on with a bulb in it, the lamp lights a pool around itself in its
+//! bulb's color, and a carried flashlight follows the cursor. The render pass
+//! in [`crate::lighting_pass`] applies it on top of the finished frame.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::components::{GameRng, UpdateSet};
+use crate::gamedata::SceneId;
+use crate::input::MousePosition;
+use crate::player::{LightbulbColor, Player, SceneState};
+use crate::progress::Flag;
+use crate::settings::Settings;
+
+/// How much of the scene shows with the lamp off.
+const DARK_AMBIENT: f32 = 0.35;
+/// Away from the lamp, with the lamp on.
+const LIT_AMBIENT: f32 = 0.7;
+const FLASHLIGHT_RADIUS: f32 = 220.0;
+/// How often a flickering lamp changes brightness.
+const FLICKER_STEP: Duration = Duration::from_millis(60);
+/// A flickering lamp with reduced motion on holds this level instead.
+const STEADY_FLICKER_LEVEL: f32 = 0.6;
+
+/// Where the lamp's shade is in the scenes that show it, and how far its
+/// light reaches.
+const LAMPS: [(SceneId, Vec2, f32); 2] = [
+    (SceneId::Desk, Vec2::new(-250.0, 210.0), 650.0),
+    (SceneId::Lamp, Vec2::new(350.0, -110.0), 1100.0),
+];
+
+/// Makes the lamp flicker for a while.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Flicker(pub Duration);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointLight {
+    pub position: Vec2,
+    pub radius: f32,
+    pub color: LinearRgba,
+}
+
+#[derive(Resource, Debug)]
+pub struct Lighting {
+    /// Brightness everywhere, from 0 for black to 1 for the image as drawn.
+    pub ambient: f32,
+    /// Only in scenes where the lamp is in view.
+    pub lamp: Option<PointLight>,
+    pub flashlight: Option<PointLight>,
+    flicker: Timer,
+    flicker_step: Timer,
+    /// How bright the flickering lamp currently is.
+    flicker_level: f32,
+}
+
+impl Default for Lighting {
+    fn default() -> Self {
+        let mut flicker = Timer::new(Duration::ZERO, TimerMode::Once);
+        flicker.tick(Duration::ZERO);
+        Self {
+            ambient: DARK_AMBIENT,
+            lamp: None,
+            flashlight: None,
+            flicker,
+            flicker_step: Timer::new(FLICKER_STEP, TimerMode::Repeating),
+            flicker_level: 1.0,
+        }
+    }
+}
+
+const fn bulb_light(color: LightbulbColor) -> LinearRgba {
+    match color {
+        LightbulbColor::Red => LinearRgba::rgb(1.0, 0.45, 0.4),
+        LightbulbColor::Green => LinearRgba::rgb(0.5, 1.0, 0.45),
+    }
+}
+
+fn flicker(
+    time: Res<Time>,
+    mut rng: ResMut<GameRng>,
+    settings: Res<Settings>,
+    mut lighting: ResMut<Lighting>,
+    mut flickers: EventReader<Flicker>,
+) {
+    for Flicker(duration) in flickers.read() {
+        lighting.flicker = Timer::new(*duration, TimerMode::Once);
+    }
+    if lighting.flicker.tick(time.delta()).finished() {
+        lighting.flicker_level = 1.0;
+        return;
+    }
+    if lighting.flicker_step.tick(time.delta()).just_finished() {
+        let level = if rng.0.gen_bool(0.3) {
+            0.0
+        } else {
+            rng.0.gen_range(0.4..1.0)
+        };
+        // Still drawn, so that a session plays out the same whatever the
+        // settings.
+        lighting.flicker_level = if settings.reduced_motion {
+            STEADY_FLICKER_LEVEL
+        } else {
+            level
+        };
+    }
+}
+
+fn update_lighting(
+    player: Res<Player>,
+    mouse_pos: Res<MousePosition>,
+    mut lighting: ResMut<Lighting>,
+) {
+    let scene = match player.scene {
+        SceneState::Active(scene)
+        | SceneState::Transitioning(_, scene, _)
+        | SceneState::ForceTransition(_, scene) => scene,
+    };
+    let bulb = player.lamp_light();
+    let level = lighting.flicker_level;
+
+    lighting.ambient = match bulb {
+        Some(_) => DARK_AMBIENT + (LIT_AMBIENT - DARK_AMBIENT) * level,
+        None => DARK_AMBIENT,
+    };
+    lighting.lamp = bulb.and_then(|bulb| {
+        let (_, position, radius) = LAMPS.iter().find(|(lamp, ..)| *lamp == scene)?;
+        Some(PointLight {
+            position: *position,
+            radius: *radius,
+            color: bulb_light(bulb) * level,
+        })
+    });
+    lighting.flashlight = player.has(Flag::HasFlashlight).then_some(PointLight {
+        position: mouse_pos.0,
+        radius: FLASHLIGHT_RADIUS,
+        color: LinearRgba::rgb(1.0, 0.95, 0.8),
+    });
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_event::<Flicker>();
+    app.init_resource::<Lighting>();
+    app.add_systems(
+        Update,
+        (flicker, update_lighting)
+            .chain()
+            .in_set(UpdateSet::PostScene),
+    );
+}
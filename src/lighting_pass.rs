@@ -0,0 +1,244 @@
+//! Post processing pass that darkens the finished frame according to
+//! [`Lighting`]. Set up the same way as the pixelate pass.
+
+use bevy::{
+    core_pipeline::{
+        core_2d::graph::{Core2d, Node2d},
+        fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    },
+    ecs::query::QueryItem,
+    prelude::*,
+    render::{
+        extract_component::{
+            ComponentUniforms, DynamicUniformIndex, ExtractComponentPlugin, UniformComponentPlugin,
+        },
+        render_graph::{
+            NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner,
+        },
+        render_resource::{
+            binding_types::{sampler, texture_2d, uniform_buffer},
+            *,
+        },
+        renderer::{RenderContext, RenderDevice},
+        texture::BevyDefault,
+        view::ViewTarget,
+        RenderApp,
+    },
+};
+
+use crate::game_state::GameState;
+use crate::lighting::{Lighting, PointLight};
+use crate::pixelate::PostProcessLabel;
+
+pub struct LightingPassPlugin;
+
+impl Plugin for LightingPassPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            ExtractComponentPlugin::<LightingSettings>::default(),
+            UniformComponentPlugin::<LightingSettings>::default(),
+        ));
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .add_render_graph_node::<ViewNodeRunner<LightingNode>>(Core2d, LightingLabel)
+            // Runs after the pixelate pass, which has to be added first, so
+            // that the edges of the light pools stay smooth.
+            .add_render_graph_edges(
+                Core2d,
+                (
+                    Node2d::Tonemapping,
+                    PostProcessLabel,
+                    LightingLabel,
+                    Node2d::EndMainPassPostProcessing,
+                ),
+            );
+        app.add_systems(PostUpdate, update_settings);
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app.init_resource::<LightingPipeline>();
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+struct LightingLabel;
+
+#[derive(Default)]
+struct LightingNode;
+
+impl ViewNode for LightingNode {
+    type ViewQuery = (
+        &'static ViewTarget,
+        &'static LightingSettings,
+        &'static DynamicUniformIndex<LightingSettings>,
+    );
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (view_target, _settings, settings_index): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let lighting_pipeline = world.resource::<LightingPipeline>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let Some(pipeline) = pipeline_cache.get_render_pipeline(lighting_pipeline.pipeline_id)
+        else {
+            return Ok(());
+        };
+        let settings_uniforms = world.resource::<ComponentUniforms<LightingSettings>>();
+        let Some(settings_binding) = settings_uniforms.uniforms().binding() else {
+            return Ok(());
+        };
+
+        let post_process = view_target.post_process_write();
+        let bind_group = render_context.render_device().create_bind_group(
+            "lighting_bind_group",
+            &lighting_pipeline.layout,
+            &BindGroupEntries::sequential((
+                post_process.source,
+                &lighting_pipeline.sampler,
+                settings_binding.clone(),
+            )),
+        );
+
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("lighting_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: post_process.destination,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[settings_index.index()]);
+        render_pass.draw(0..3, 0..1);
+
+        Ok(())
+    }
+}
+
+#[derive(Resource)]
+struct LightingPipeline {
+    layout: BindGroupLayout,
+    sampler: Sampler,
+    pipeline_id: CachedRenderPipelineId,
+}
+
+impl FromWorld for LightingPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let layout = render_device.create_bind_group_layout(
+            "lighting_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                    uniform_buffer::<LightingSettings>(true),
+                ),
+            ),
+        );
+        let sampler = render_device.create_sampler(&SamplerDescriptor::default());
+        let shader = world.load_asset("shaders/lighting.wgsl");
+
+        let pipeline_id =
+            world
+                .resource_mut::<PipelineCache>()
+                .queue_render_pipeline(RenderPipelineDescriptor {
+                    label: Some("lighting_pipeline".into()),
+                    layout: vec![layout.clone()],
+                    vertex: fullscreen_shader_vertex_state(),
+                    fragment: Some(FragmentState {
+                        shader,
+                        shader_defs: vec![],
+                        entry_point: "fragment".into(),
+                        targets: vec![Some(ColorTargetState {
+                            format: TextureFormat::bevy_default(),
+                            blend: None,
+                            write_mask: ColorWrites::ALL,
+                        })],
+                    }),
+                    primitive: PrimitiveState::default(),
+                    depth_stencil: None,
+                    multisample: MultisampleState::default(),
+                    push_constant_ranges: vec![],
+                });
+
+        Self {
+            layout,
+            sampler,
+            pipeline_id,
+        }
+    }
+}
+
+pub use uniform::LightingSettings;
+
+// The `ShaderType` derive checks every field with a function nothing calls.
+mod uniform {
+    #![allow(dead_code)]
+
+    use bevy::{
+        prelude::*,
+        render::{extract_component::ExtractComponent, render_resource::ShaderType},
+    };
+
+    /// [`Lighting`](crate::lighting::Lighting) as the shader sees it.
+    /// Positions are in world units and a light with a radius of 0 is off.
+    #[derive(Component, Default, Clone, Copy, ExtractComponent, ShaderType)]
+    pub struct LightingSettings {
+        pub lamp_color: Vec4,
+        pub flashlight_color: Vec4,
+        pub lamp_position: Vec2,
+        pub flashlight_position: Vec2,
+        pub ambient: f32,
+        pub lamp_radius: f32,
+        pub flashlight_radius: f32,
+        // WebGL2 structs must be 16 byte aligned.
+        pub _webgl2_padding: f32,
+    }
+}
+
+const OFF: PointLight = PointLight {
+    position: Vec2::ZERO,
+    radius: 0.0,
+    color: LinearRgba::BLACK,
+};
+
+fn update_settings(
+    lighting: Res<Lighting>,
+    state: Res<State<GameState>>,
+    mut settings: Query<&mut LightingSettings>,
+) {
+    // Menus and the ending screen aren't in the room, so they stay fully lit.
+    let (lamp, flashlight, ambient) = match state.get() {
+        GameState::Playing => (
+            lighting.lamp.unwrap_or(OFF),
+            lighting.flashlight.unwrap_or(OFF),
+            lighting.ambient,
+        ),
+        _ => (OFF, OFF, 1.0),
+    };
+    for mut settings in &mut settings {
+        *settings = LightingSettings {
+            lamp_color: lamp.color.to_vec4(),
+            flashlight_color: flashlight.color.to_vec4(),
+            lamp_position: lamp.position,
+            flashlight_position: flashlight.position,
+            ambient,
+            lamp_radius: lamp.radius,
+            flashlight_radius: flashlight.radius,
+            _webgl2_padding: 0.0,
+        };
+    }
+}
@@ -10,13 +10,32 @@ use bevy::prelude::*;
 mod audio;
 mod background;
 mod camera;
+mod combination_lock;
 mod components;
+mod detuning;
+mod endings;
+mod event_log;
+mod game_state;
 mod gamedata;
+mod hints;
 mod input;
+mod lighting;
+mod lighting_pass;
+mod menu;
+mod mixer;
+mod morse;
+mod phone;
 mod pixelate;
 mod player;
+mod progress;
+mod radio_static;
+mod replay;
 mod right_speaker;
 mod scenes;
+mod settings;
+mod solutions;
+#[cfg(test)]
+mod tests;
 mod tv;
 
 use background::background_plugin;
@@ -26,7 +45,6 @@ use tv::tv_plugin;
 
 fn main() {
     App::new()
-        .configure_sets(Update, UpdateSet::canon_order().chain())
         .add_plugins(DefaultPlugins.set(AssetPlugin {
             // Wasm builds will check for meta files (that don't exist) if this isn't set.
             // This causes errors and even panics in web builds on itch.
@@ -34,16 +52,40 @@ fn main() {
             meta_check: AssetMetaCheck::Never,
             ..default()
         }))
-        .add_plugins((
-            tv_plugin,
-            camera_plugin,
+        .add_plugins((camera_plugin, gameplay_plugin))
+        .add_plugins((pixelate::PixelatePlugin, lighting_pass::LightingPassPlugin))
+        .run();
+}
+
+/// Everything but the window, camera and render passes, so that the game can
+/// also be driven headless.
+pub fn gameplay_plugin(app: &mut App) {
+    app.configure_sets(Update, UpdateSet::canon_order().chain());
+    app.add_plugins((
+        tv_plugin,
+        (
             input::plugin,
-            background_plugin,
+            game_state::plugin,
+            menu::plugin,
+            settings::plugin,
+        ),
+        background_plugin,
+        (
             audio::plugin,
-            player::plugin,
-            scenes::plugin,
-            right_speaker::plugin,
-        ))
-        .add_plugins(pixelate::PixelatePlugin)
-        .run();
+            mixer::plugin,
+            radio_static::plugin,
+            detuning::plugin,
+        ),
+        player::plugin,
+        combination_lock::plugin,
+        phone::plugin,
+        lighting::plugin,
+        (progress::plugin, endings::plugin),
+        scenes::plugin,
+        solutions::plugin,
+        right_speaker::plugin,
+        event_log::plugin,
+        hints::plugin,
+        replay::plugin,
+    ));
 }
@@ -0,0 +1,604 @@
+//! The main menu the game opens on, the pause overlay Escape brings up over a
+//! run, and the settings screen either of them leads to.
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+
+use crate::camera::{HORIZONTAL_RESOLUTION, VERTICAL_RESOLUTION};
+use crate::components::{ClickableArea, ClickableShape, GameRng, Keyboard, Rectangle, UpdateSet};
+use crate::endings::Ending;
+use crate::game_state::GameState;
+use crate::input::MousePosition;
+use crate::player::{Player, ResetUniverse};
+use crate::settings::{self, Settings, Volume, ALL_VOLUMES};
+use crate::solutions::{self, Solutions};
+
+const TITLE: &str = "RADIO SPOOKY";
+const PAUSED: &str = "PAUSED";
+const PAUSE_KEY: KeyCode = KeyCode::Escape;
+
+/// Above the ending screen.
+const Z: f32 = 30.0;
+const TITLE_Y: f32 = 250.0;
+const FIRST_ITEM_Y: f32 = 60.0;
+const ITEM_SPACING: f32 = 100.0;
+const ITEM_SIZE: Vec2 = Vec2::new(600.0, 90.0);
+
+const SETTINGS_TITLE_Y: f32 = 420.0;
+const FIRST_ROW_Y: f32 = 300.0;
+const ROW_SPACING: f32 = 80.0;
+const BACK_Y: f32 = -400.0;
+const LABEL_X: f32 = -600.0;
+/// The left end of the sliders, which the other values are lined up with.
+const VALUE_X: f32 = 0.0;
+const VALUE_SIZE: Vec2 = Vec2::new(500.0, 70.0);
+const SLIDER_WIDTH: f32 = 500.0;
+const KNOB_SIZE: Vec2 = Vec2::new(24.0, 48.0);
+const PERCENT_X: f32 = 560.0;
+const PERCENT_SIZE: Vec2 = Vec2::new(220.0, 70.0);
+
+const TEXT_COLOR: Color = Color::srgb(0.85, 0.85, 0.8);
+const HOVER_COLOR: Color = Color::srgb(0.0, 1.0, 1.0);
+const TRACK_COLOR: Color = Color::srgb(0.3, 0.3, 0.3);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MenuItem {
+    Continue,
+    NewGame,
+    Settings,
+    Quit,
+    Resume,
+    QuitToMenu,
+}
+
+impl MenuItem {
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Continue => "CONTINUE",
+            Self::NewGame => "NEW GAME",
+            Self::Settings => "SETTINGS",
+            Self::Quit => "QUIT",
+            Self::Resume => "RESUME",
+            Self::QuitToMenu => "QUIT TO MENU",
+        }
+    }
+}
+
+/// The settings that are picked from a list rather than slid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Choice {
+    Fullscreen,
+    Pixelation,
+    TextSize,
+    ReducedMotion,
+}
+
+const ALL_CHOICES: [Choice; 4] = [
+    Choice::Fullscreen,
+    Choice::Pixelation,
+    Choice::TextSize,
+    Choice::ReducedMotion,
+];
+
+impl Choice {
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Fullscreen => "DISPLAY",
+            Self::Pixelation => "PIXELATION",
+            Self::TextSize => "TEXT SIZE",
+            Self::ReducedMotion => "REDUCED MOTION",
+        }
+    }
+
+    fn value(self, settings: &Settings) -> &'static str {
+        let on_off = |on| if on { "ON" } else { "OFF" };
+        match self {
+            Self::Fullscreen if settings.fullscreen => "FULLSCREEN",
+            Self::Fullscreen => "WINDOWED",
+            Self::Pixelation => on_off(settings.pixelation),
+            Self::TextSize => settings.text_size.label(),
+            Self::ReducedMotion => on_off(settings.reduced_motion),
+        }
+    }
+
+    /// Moves on to the next value, wrapping around.
+    fn cycle(self, settings: &mut Settings) {
+        match self {
+            Self::Fullscreen => settings.fullscreen = !settings.fullscreen,
+            Self::Pixelation => settings.pixelation = !settings.pixelation,
+            Self::TextSize => settings.text_size = settings.text_size.next(),
+            Self::ReducedMotion => settings.reduced_motion = !settings.reduced_motion,
+        }
+    }
+}
+
+/// Whether a run has been played this session, so there is one to continue.
+#[derive(Resource, Default)]
+struct RunStarted(bool);
+
+/// The menu the settings screen goes back to.
+#[derive(Resource)]
+struct SettingsReturn(GameState);
+
+impl Default for SettingsReturn {
+    fn default() -> Self {
+        Self(GameState::MainMenu)
+    }
+}
+
+/// The volume slider being dragged, if any.
+#[derive(Resource, Default)]
+struct Dragging(Option<Volume>);
+
+/// Lights up while the cursor is over it.
+#[derive(Component)]
+struct Hover;
+
+#[derive(Component)]
+struct MenuButton(MenuItem);
+
+#[derive(Component)]
+struct ChoiceButton(Choice);
+
+#[derive(Component)]
+struct BackButton;
+
+#[derive(Component)]
+struct Slider(Volume);
+
+#[derive(Component)]
+struct SliderKnob(Volume);
+
+#[derive(Component)]
+struct SliderPercent(Volume);
+
+fn text_style(asset_server: &AssetServer, font_size: f32) -> TextStyle {
+    TextStyle {
+        font: asset_server.load("fonts/FiraMono-Regular.ttf"),
+        font_size,
+        color: TEXT_COLOR,
+    }
+}
+
+fn spawn_backdrop(commands: &mut Commands, state: GameState, color: Color) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(Vec2::new(HORIZONTAL_RESOLUTION, VERTICAL_RESOLUTION)),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 0.0, Z),
+            ..default()
+        },
+        StateScoped(state),
+    ));
+}
+
+fn spawn_text(
+    commands: &mut Commands,
+    state: GameState,
+    value: &str,
+    style: TextStyle,
+    pos: Vec2,
+    anchor: Anchor,
+) -> Entity {
+    commands
+        .spawn((
+            Text2dBundle {
+                text: Text::from_section(value, style),
+                text_anchor: anchor,
+                transform: Transform::from_translation(pos.extend(Z + 1.0)),
+                ..default()
+            },
+            StateScoped(state),
+        ))
+        .id()
+}
+
+fn spawn_menu(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    state: GameState,
+    backdrop: Color,
+    title: &str,
+    items: &[MenuItem],
+) {
+    spawn_backdrop(commands, state, backdrop);
+    spawn_text(
+        commands,
+        state,
+        title,
+        text_style(asset_server, 110.0),
+        Vec2::new(0.0, TITLE_Y),
+        Anchor::Center,
+    );
+
+    for (index, item) in items.iter().enumerate() {
+        let pos = Vec2::new(0.0, FIRST_ITEM_Y - ITEM_SPACING * index as f32);
+        let text = spawn_text(
+            commands,
+            state,
+            item.label(),
+            text_style(asset_server, 60.0),
+            pos,
+            Anchor::Center,
+        );
+        commands.entity(text).insert((
+            ClickableShape::Rectangle(Rectangle::from_pos_width_height(
+                pos,
+                ITEM_SIZE.x,
+                ITEM_SIZE.y,
+            )),
+            MenuButton(*item),
+            Hover,
+        ));
+    }
+}
+
+fn spawn_main_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    player: Res<Player>,
+    run_started: Res<RunStarted>,
+) {
+    let mut items = vec![];
+    if run_started.0 && Ending::reached(&player.flags).is_none() {
+        items.push(MenuItem::Continue);
+    }
+    items.extend([MenuItem::NewGame, MenuItem::Settings]);
+    // Closing the tab is how a web page quits.
+    if !cfg!(target_arch = "wasm32") {
+        items.push(MenuItem::Quit);
+    }
+    spawn_menu(
+        &mut commands,
+        &asset_server,
+        GameState::MainMenu,
+        Color::BLACK,
+        TITLE,
+        &items,
+    );
+}
+
+fn spawn_pause_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+    spawn_menu(
+        &mut commands,
+        &asset_server,
+        GameState::Paused,
+        Color::BLACK.with_alpha(0.8),
+        PAUSED,
+        &[MenuItem::Resume, MenuItem::Settings, MenuItem::QuitToMenu],
+    );
+}
+
+fn spawn_settings(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+    settings_return: Res<SettingsReturn>,
+) {
+    let state = GameState::Settings;
+    // Over a paused run the room still shows through, as it does behind the
+    // pause menu.
+    let backdrop = match settings_return.0 {
+        GameState::Paused => Color::BLACK.with_alpha(0.8),
+        _ => Color::BLACK,
+    };
+    spawn_backdrop(&mut commands, state, backdrop);
+    spawn_text(
+        &mut commands,
+        state,
+        "SETTINGS",
+        text_style(&asset_server, 90.0),
+        Vec2::new(0.0, SETTINGS_TITLE_Y),
+        Anchor::Center,
+    );
+
+    let row_y = |row: usize| FIRST_ROW_Y - ROW_SPACING * row as f32;
+    for (row, volume) in ALL_VOLUMES.into_iter().enumerate() {
+        let y = row_y(row);
+        spawn_text(
+            &mut commands,
+            state,
+            volume.label(),
+            text_style(&asset_server, 50.0),
+            Vec2::new(LABEL_X, y),
+            Anchor::CenterLeft,
+        );
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: TRACK_COLOR,
+                    custom_size: Some(Vec2::new(SLIDER_WIDTH, 8.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(VALUE_X + SLIDER_WIDTH / 2.0, y, Z + 1.0),
+                ..default()
+            },
+            ClickableShape::Rectangle(Rectangle::from_pos_width_height(
+                Vec2::new(VALUE_X + SLIDER_WIDTH / 2.0, y),
+                SLIDER_WIDTH + KNOB_SIZE.x,
+                VALUE_SIZE.y,
+            )),
+            Slider(volume),
+            StateScoped(state),
+        ));
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: TEXT_COLOR,
+                    custom_size: Some(KNOB_SIZE),
+                    ..default()
+                },
+                transform: Transform::from_translation(
+                    knob_position(volume, &settings, y).extend(Z + 2.0),
+                ),
+                ..default()
+            },
+            SliderKnob(volume),
+            StateScoped(state),
+        ));
+        // Clicking the level mutes the track, and clicking it again brings it
+        // back at the same level.
+        let percent = spawn_text(
+            &mut commands,
+            state,
+            &level(volume, &settings),
+            text_style(&asset_server, 50.0),
+            Vec2::new(PERCENT_X, y),
+            Anchor::CenterLeft,
+        );
+        commands.entity(percent).insert((
+            ClickableShape::Rectangle(Rectangle::from_pos_width_height(
+                Vec2::new(PERCENT_X + PERCENT_SIZE.x / 2.0, y),
+                PERCENT_SIZE.x,
+                PERCENT_SIZE.y,
+            )),
+            SliderPercent(volume),
+            Hover,
+        ));
+    }
+
+    for (index, choice) in ALL_CHOICES.into_iter().enumerate() {
+        let y = row_y(ALL_VOLUMES.len() + index);
+        spawn_text(
+            &mut commands,
+            state,
+            choice.label(),
+            text_style(&asset_server, 50.0),
+            Vec2::new(LABEL_X, y),
+            Anchor::CenterLeft,
+        );
+        let value = spawn_text(
+            &mut commands,
+            state,
+            choice.value(&settings),
+            text_style(&asset_server, 50.0),
+            Vec2::new(VALUE_X, y),
+            Anchor::CenterLeft,
+        );
+        commands.entity(value).insert((
+            ClickableShape::Rectangle(Rectangle::from_pos_width_height(
+                Vec2::new(VALUE_X + VALUE_SIZE.x / 2.0, y),
+                VALUE_SIZE.x,
+                VALUE_SIZE.y,
+            )),
+            ChoiceButton(choice),
+            Hover,
+        ));
+    }
+
+    let back = spawn_text(
+        &mut commands,
+        state,
+        "BACK",
+        text_style(&asset_server, 60.0),
+        Vec2::new(0.0, BACK_Y),
+        Anchor::Center,
+    );
+    commands.entity(back).insert((
+        ClickableShape::Rectangle(Rectangle::from_pos_width_height(
+            Vec2::new(0.0, BACK_Y),
+            ITEM_SIZE.x,
+            ITEM_SIZE.y,
+        )),
+        BackButton,
+        Hover,
+    ));
+}
+
+fn knob_position(volume: Volume, settings: &Settings, y: f32) -> Vec2 {
+    Vec2::new(VALUE_X + SLIDER_WIDTH * settings.volume(volume) as f32, y)
+}
+
+fn level(volume: Volume, settings: &Settings) -> String {
+    if settings.muted(volume) {
+        "MUTED".to_string()
+    } else {
+        format!("{}%", (settings.volume(volume) * 100.0).round())
+    }
+}
+
+fn highlight(
+    mouse_pos: Res<MousePosition>,
+    mut buttons: Query<(&ClickableShape, &mut Text), With<Hover>>,
+) {
+    for (shape, mut text) in buttons.iter_mut() {
+        let color = if shape.contains(mouse_pos.0) {
+            HOVER_COLOR
+        } else {
+            TEXT_COLOR
+        };
+        if text.sections[0].style.color != color {
+            text.sections[0].style.color = color;
+        }
+    }
+}
+
+fn pick(
+    mouse_pos: Res<MousePosition>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    buttons: Query<(&MenuButton, &ClickableShape)>,
+    state: Res<State<GameState>>,
+    mut player: ResMut<Player>,
+    mut solutions: ResMut<Solutions>,
+    mut rng: ResMut<GameRng>,
+    mut settings_return: ResMut<SettingsReturn>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut reset_universe: EventWriter<ResetUniverse>,
+    mut app_exit: EventWriter<AppExit>,
+) {
+    if !mouse_button.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let Some((MenuButton(item), _)) = buttons
+        .iter()
+        .find(|(_, shape)| shape.contains(mouse_pos.0))
+    else {
+        return;
+    };
+
+    match item {
+        MenuItem::Continue | MenuItem::Resume => next_state.set(GameState::Playing),
+        MenuItem::NewGame => {
+            player.restart();
+            *solutions = solutions::new_run(&mut rng);
+            reset_universe.send(ResetUniverse);
+            next_state.set(GameState::Playing);
+        }
+        MenuItem::Settings => {
+            settings_return.0 = *state.get();
+            next_state.set(GameState::Settings);
+        }
+        MenuItem::Quit => {
+            app_exit.send(AppExit::Success);
+        }
+        MenuItem::QuitToMenu => next_state.set(GameState::MainMenu),
+    }
+}
+
+fn drag_sliders(
+    mouse_pos: Res<MousePosition>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    sliders: Query<(&Slider, &ClickableShape)>,
+    mut dragging: ResMut<Dragging>,
+    mut settings: ResMut<Settings>,
+) {
+    if mouse_button.just_pressed(MouseButton::Left) {
+        dragging.0 = sliders
+            .iter()
+            .find(|(_, shape)| shape.contains(mouse_pos.0))
+            .map(|(Slider(volume), _)| *volume);
+    }
+    if !mouse_button.pressed(MouseButton::Left) {
+        dragging.0 = None;
+    }
+    let Some(volume) = dragging.0 else {
+        return;
+    };
+    let mut dragged = settings.clone();
+    dragged.set_volume(volume, ((mouse_pos.0.x - VALUE_X) / SLIDER_WIDTH) as f64);
+    // Changing the settings is what applies them, so they're only touched
+    // when the value actually moves.
+    if dragged != *settings {
+        *settings = dragged;
+    }
+}
+
+fn pick_settings(
+    mouse_pos: Res<MousePosition>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    keyboard: Keyboard,
+    choices: Query<(&ChoiceButton, &ClickableShape)>,
+    percents: Query<(&SliderPercent, &ClickableShape)>,
+    back: Query<&ClickableShape, With<BackButton>>,
+    settings_return: Res<SettingsReturn>,
+    mut settings: ResMut<Settings>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keyboard.just_pressed(PAUSE_KEY) {
+        next_state.set(settings_return.0);
+        return;
+    }
+    if !mouse_button.just_pressed(MouseButton::Left) {
+        return;
+    }
+    if back.iter().any(|shape| shape.contains(mouse_pos.0)) {
+        next_state.set(settings_return.0);
+    }
+    for (ChoiceButton(choice), shape) in choices.iter() {
+        if shape.contains(mouse_pos.0) {
+            choice.cycle(&mut settings);
+        }
+    }
+    for (SliderPercent(volume), shape) in percents.iter() {
+        if shape.contains(mouse_pos.0) {
+            settings.toggle_muted(*volume);
+        }
+    }
+}
+
+fn update_settings_screen(
+    settings: Res<Settings>,
+    mut knobs: Query<(&SliderKnob, &mut Transform)>,
+    mut percents: Query<(&SliderPercent, &mut Text), Without<ChoiceButton>>,
+    mut choices: Query<(&ChoiceButton, &mut Text), Without<SliderPercent>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    for (SliderKnob(volume), mut transform) in knobs.iter_mut() {
+        let y = transform.translation.y;
+        transform.translation = knob_position(*volume, &settings, y).extend(Z + 2.0);
+    }
+    for (SliderPercent(volume), mut text) in percents.iter_mut() {
+        text.sections[0].value = level(*volume, &settings);
+    }
+    for (ChoiceButton(choice), mut text) in choices.iter_mut() {
+        text.sections[0].value = choice.value(&settings).to_string();
+    }
+}
+
+fn start_run(mut run_started: ResMut<RunStarted>) {
+    run_started.0 = true;
+}
+
+// Runs after the scene so that a lock holding onto the keyboard gets to let
+// go of it first.
+fn pause(keyboard: Keyboard, mut next_state: ResMut<NextState<GameState>>) {
+    if keyboard.just_pressed(PAUSE_KEY) {
+        next_state.set(GameState::Paused);
+    }
+}
+
+fn unpause(keyboard: Keyboard, mut next_state: ResMut<NextState<GameState>>) {
+    if keyboard.just_pressed(PAUSE_KEY) {
+        next_state.set(GameState::Playing);
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<RunStarted>();
+    app.init_resource::<SettingsReturn>();
+    app.init_resource::<Dragging>();
+    app.add_systems(OnEnter(GameState::Playing), start_run);
+    app.add_systems(OnEnter(GameState::MainMenu), spawn_main_menu);
+    app.add_systems(OnEnter(GameState::Paused), spawn_pause_menu);
+    app.add_systems(OnEnter(GameState::Settings), spawn_settings);
+    app.add_systems(OnExit(GameState::Settings), settings::save_settings);
+    app.add_systems(Update, pause.in_set(UpdateSet::PostScene));
+    app.add_systems(Update, unpause.run_if(in_state(GameState::Paused)));
+    app.add_systems(
+        Update,
+        pick.run_if(in_state(GameState::MainMenu).or_else(in_state(GameState::Paused))),
+    );
+    app.add_systems(
+        Update,
+        (drag_sliders, pick_settings, update_settings_screen)
+            .chain()
+            .run_if(in_state(GameState::Settings)),
+    );
+    app.add_systems(Update, highlight);
+}
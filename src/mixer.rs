@@ -0,0 +1,195 @@
+//! Typed audio channels, so that each kind of sound can be turned down, muted
+//! or paused without touching the others.
+
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+
+use crate::components::UpdateSet;
+use crate::settings::{Settings, Volume};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Track {
+    Radio,
+    Static,
+    Sfx,
+    Tv,
+    Music,
+    Ui,
+}
+
+pub const ALL_TRACKS: [Track; 6] = [
+    Track::Radio,
+    Track::Static,
+    Track::Sfx,
+    Track::Tv,
+    Track::Music,
+    Track::Ui,
+];
+
+impl Track {
+    /// The volume slider the track follows.
+    const fn setting(self) -> Volume {
+        match self {
+            Self::Radio => Volume::Radio,
+            Self::Static => Volume::Static,
+            Self::Sfx | Self::Tv | Self::Ui => Volume::Sfx,
+            Self::Music => Volume::Music,
+        }
+    }
+}
+
+/// Stations on the AM band.
+#[derive(Resource)]
+pub struct RadioChannel;
+
+/// The noise between stations.
+#[derive(Resource)]
+pub struct StaticChannel;
+
+/// Sounds in the room: the phone, the door and the explosion.
+#[derive(Resource)]
+pub struct SfxChannel;
+
+/// The game on the TV.
+#[derive(Resource)]
+pub struct TvChannel;
+
+/// The music over the endings.
+#[derive(Resource)]
+pub struct MusicChannel;
+
+/// Feedback from the locks.
+#[derive(Resource)]
+pub struct UiChannel;
+
+trait MixerChannel: Resource {
+    const TRACK: Track;
+}
+
+impl MixerChannel for SfxChannel {
+    const TRACK: Track = Track::Sfx;
+}
+
+impl MixerChannel for TvChannel {
+    const TRACK: Track = Track::Tv;
+}
+
+impl MixerChannel for MusicChannel {
+    const TRACK: Track = Track::Music;
+}
+
+impl MixerChannel for UiChannel {
+    const TRACK: Track = Track::Ui;
+}
+
+/// Where the mix is applied to the sounds, after the scene has had its say and
+/// whether or not the game is running.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ApplyMix;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrackMix {
+    pub volume: f64,
+    pub muted: bool,
+    pub paused: bool,
+}
+
+impl Default for TrackMix {
+    fn default() -> Self {
+        Self {
+            volume: 1.0,
+            muted: false,
+            paused: false,
+        }
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct Mixer([TrackMix; ALL_TRACKS.len()]);
+
+impl Mixer {
+    pub fn track(&self, track: Track) -> &TrackMix {
+        &self.0[track as usize]
+    }
+
+    pub fn track_mut(&mut self, track: Track) -> &mut TrackMix {
+        &mut self.0[track as usize]
+    }
+
+    /// The volume the track actually plays at.
+    pub fn gain(&self, track: Track) -> f64 {
+        let mix = self.track(track);
+        if mix.muted {
+            0.0
+        } else {
+            mix.volume
+        }
+    }
+}
+
+fn follow_settings(settings: Res<Settings>, mut mixer: ResMut<Mixer>) {
+    if !settings.is_changed() {
+        return;
+    }
+    for track in ALL_TRACKS {
+        let volume = settings.volume(track.setting());
+        let muted = settings.muted(track.setting());
+        let mix = mixer.track(track);
+        if (mix.volume, mix.muted) != (volume, muted) {
+            let mix = mixer.track_mut(track);
+            mix.volume = volume;
+            mix.muted = muted;
+        }
+    }
+}
+
+// Radio stations and static set their own volume whenever the dial moves,
+// which a channel-wide volume would undo, and resuming their channel would also
+// start the stations that are tuned away. So the radio and the static apply
+// their track's mix to their own sounds, in `ApplyMix` like the rest.
+fn apply_mix<T: MixerChannel>(
+    mixer: Res<Mixer>,
+    channel: Res<AudioChannel<T>>,
+    mut applied: Local<Option<TrackMix>>,
+) {
+    let mix = *mixer.track(T::TRACK);
+    if *applied == Some(mix) {
+        return;
+    }
+    if applied.map(|old| (old.volume, old.muted)) != Some((mix.volume, mix.muted)) {
+        channel.set_volume(mixer.gain(T::TRACK));
+    }
+    if applied.map_or(mix.paused, |old| old.paused != mix.paused) {
+        if mix.paused {
+            channel.pause();
+        } else {
+            channel.resume();
+        }
+    }
+    *applied = Some(mix);
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_audio_channel::<RadioChannel>()
+        .add_audio_channel::<StaticChannel>()
+        .add_audio_channel::<SfxChannel>()
+        .add_audio_channel::<TvChannel>()
+        .add_audio_channel::<MusicChannel>()
+        .add_audio_channel::<UiChannel>();
+    app.init_resource::<Mixer>();
+    app.configure_sets(
+        Update,
+        ApplyMix.after(follow_settings).after(UpdateSet::PostScene),
+    );
+    app.add_systems(Update, follow_settings);
+    app.add_systems(
+        Update,
+        (
+            apply_mix::<SfxChannel>,
+            apply_mix::<TvChannel>,
+            apply_mix::<MusicChannel>,
+            apply_mix::<UiChannel>,
+        )
+            .in_set(ApplyMix),
+    );
+}
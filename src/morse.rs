@@ -0,0 +1,72 @@
+//! The morse station's broadcast, keyed from whatever word it's sending
+//! rather than recorded, so that it always says what the translator shows.
+
+use std::f32::consts::TAU;
+
+use bevy_kira_audio::prelude::*;
+
+const SAMPLE_RATE: u32 = 44_100;
+const TONE_HZ: f32 = 700.0;
+const AMPLITUDE: f32 = 0.5;
+/// The length of a dot. Dashes and the gaps are counted in these.
+const UNIT_SECONDS: f32 = 0.09;
+// Short ramps at both ends of every tone, so that they don't click.
+const RAMP_SECONDS: f32 = 0.005;
+/// Silence between one sending of the word and the next.
+const REPEAT_UNITS: usize = 20;
+
+/// Dots and dashes for A to Z.
+const LETTERS: [&str; 26] = [
+    ".-", "-...", "-.-.", "-..", ".", "..-.", "--.", "....", "..", ".---", "-.-", ".-..", "--",
+    "-.", "---", ".--.", "--.-", ".-.", "...", "-", "..-", "...-", ".--", "-..-", "-.--", "--..",
+];
+
+/// `word` as runs of tone and silence, in units: a dot is one unit of tone
+/// and a dash three, with one unit of silence between them and three between
+/// letters.
+pub fn keying(word: &str) -> Vec<(bool, usize)> {
+    let mut runs = vec![];
+    for letter in word.chars().filter_map(|c| {
+        let index = (c.to_ascii_uppercase() as usize).checked_sub('A' as usize)?;
+        LETTERS.get(index)
+    }) {
+        for symbol in letter.chars() {
+            runs.push((true, if symbol == '-' { 3 } else { 1 }));
+            runs.push((false, 1));
+        }
+        if let Some((_, gap)) = runs.last_mut() {
+            *gap = 3;
+        }
+    }
+    runs.push((false, REPEAT_UNITS));
+    runs
+}
+
+/// `word` sent over and over, for a looping station.
+pub fn broadcast(word: &str) -> AudioSource {
+    let unit = (UNIT_SECONDS * SAMPLE_RATE as f32) as usize;
+    let ramp = (RAMP_SECONDS * SAMPLE_RATE as f32) as usize;
+    let mut frames = vec![];
+    for (on, units) in keying(word) {
+        let length = units * unit;
+        for i in 0..length {
+            if !on {
+                frames.push(Frame::ZERO);
+                continue;
+            }
+            let envelope = (i.min(length - i) as f32 / ramp as f32).min(1.0);
+            let t = frames.len() as f32 / SAMPLE_RATE as f32;
+            frames.push(Frame::from_mono(
+                (TAU * TONE_HZ * t).sin() * envelope * AMPLITUDE,
+            ));
+        }
+    }
+
+    AudioSource {
+        sound: StaticSoundData {
+            sample_rate: SAMPLE_RATE,
+            frames: frames.into(),
+            settings: StaticSoundSettings::default(),
+        },
+    }
+}
@@ -0,0 +1,141 @@
+//! Conversations over the phone. The other end says a node's lines one after
+//! the other, and the player answers by pressing a digit, which moves the
+//! conversation on to another node.
+
+use std::time::Duration;
+
+use crate::progress::Action;
+
+pub struct Line {
+    /// Shown on the phone's display while the line is said.
+    pub text: &'static str,
+    pub clip: Option<&'static str>,
+    pub duration: Duration,
+}
+
+impl Line {
+    pub const fn new(text: &'static str, seconds: u64) -> Self {
+        Self {
+            text,
+            clip: None,
+            duration: Duration::from_secs(seconds),
+        }
+    }
+
+    pub const fn clip(mut self, clip: &'static str) -> Self {
+        self.clip = Some(clip);
+        self
+    }
+}
+
+pub struct Choice {
+    /// The digit printed on the button.
+    pub digit: usize,
+    /// Index of the node this leads to.
+    pub next: usize,
+}
+
+pub struct Node {
+    pub lines: &'static [Line],
+    /// Performed when the conversation reaches this node.
+    pub action: Option<Action>,
+    /// Without any, the other end hangs up after the last line.
+    pub choices: &'static [Choice],
+}
+
+impl Node {
+    pub const fn says(lines: &'static [Line]) -> Self {
+        Self {
+            lines,
+            action: None,
+            choices: &[],
+        }
+    }
+
+    pub const fn performs(mut self, action: Action) -> Self {
+        self.action = Some(action);
+        self
+    }
+
+    pub const fn choices(mut self, choices: &'static [Choice]) -> Self {
+        self.choices = choices;
+        self
+    }
+
+    pub fn next(&self, digit: usize) -> Option<usize> {
+        self.choices
+            .iter()
+            .find(|choice| choice.digit == digit)
+            .map(|choice| choice.next)
+    }
+}
+
+/// Starts at the first node.
+pub struct Dialogue(pub &'static [Node]);
+
+pub const SAVE_UNIVERSE: Dialogue = Dialogue(&[
+    Node::says(&[
+        Line::new("...", 2),
+        Line::new("SWITCHBOARD.", 2),
+        Line::new("KEEP THIS UNIVERSE?", 3),
+        Line::new("1 YES    2 NO", 3),
+    ])
+    .choices(&[Choice { digit: 1, next: 1 }, Choice { digit: 2, next: 2 }]),
+    Node::says(&[Line::new("CONNECTED.", 3), Line::new("HOLD ON TO IT.", 3)])
+        .performs(Action::DialPhone),
+    Node::says(&[
+        Line::new("AS YOU WISH.", 3),
+        Line::new("DISCONNECTING IT.", 3),
+    ])
+    .performs(Action::AbandonUniverse),
+]);
+
+pub const NUMBERS_STATION: Dialogue = Dialogue(&[Node::says(&[
+    Line::new("...", 2).clip("audio/number-station.ogg"),
+    Line::new("THE SAME NUMBERS", 4),
+    Line::new("IN THE SAME ORDER", 4),
+])]);
+
+pub const OPERATOR: Dialogue = Dialogue(&[
+    Node::says(&[
+        Line::new("OPERATOR.", 2),
+        Line::new("ALL LINES OUT OF", 2),
+        Line::new("THIS UNIVERSE ARE", 2),
+        Line::new("DOWN.", 2),
+        Line::new("1 REPEAT  2 HELP", 3),
+    ])
+    .choices(&[Choice { digit: 1, next: 0 }, Choice { digit: 2, next: 1 }]),
+    Node::says(&[
+        Line::new("THE RADIO READS", 2),
+        Line::new("THE NUMBERS.", 2),
+        Line::new("THE BOARD SAYS", 2),
+        Line::new("IN WHICH ORDER.", 3),
+    ]),
+]);
+
+/// The switchboard's number dialed the right way round under green light.
+pub const SWITCHBOARD_GREEN: Dialogue = Dialogue(&[Node::says(&[
+    Line::new("SWITCHBOARD.", 2),
+    Line::new("WRONG LIGHT FOR", 2),
+    Line::new("THIS NUMBER.", 3),
+    Line::new("IN GREEN IT RUNS", 2),
+    Line::new("THE OTHER WAY.", 3),
+])]);
+
+pub const OTHER_SIDE: Dialogue = Dialogue(&[
+    Node::says(&[
+        Line::new("...", 2),
+        Line::new("OH. IT'S YOU.", 3),
+        Line::new("I'VE BEEN AT THIS", 2),
+        Line::new("DESK FOR A WHILE.", 3),
+        Line::new("TRADE PLACES?", 3),
+        Line::new("1 YES    2 NO", 3),
+    ])
+    .choices(&[Choice { digit: 1, next: 1 }, Choice { digit: 2, next: 2 }]),
+    Node::says(&[
+        Line::new("THANK YOU.", 3),
+        Line::new("IT'S YOUR DESK NOW.", 3),
+    ])
+    .performs(Action::TradeUniverse),
+    Node::says(&[Line::new("MAYBE NEXT TIME.", 3)]),
+]);
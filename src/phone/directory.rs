@@ -0,0 +1,74 @@
+//! Every number that picks up when dialed, and what the other end says.
+
+use super::dialogue::{
+    Dialogue, NUMBERS_STATION, OPERATOR, OTHER_SIDE, SAVE_UNIVERSE, SWITCHBOARD_GREEN,
+};
+use crate::gamedata::PHONE_BUTTON_LABELS;
+use crate::player::LightbulbColor;
+use crate::solutions::{Solutions, BROADCAST_DIGITS};
+
+#[derive(Clone, Copy)]
+pub struct Entry {
+    pub name: &'static str,
+    /// The digits printed on the buttons, in the order they are dialed.
+    pub number: [usize; 9],
+    pub dialogue: &'static Dialogue,
+}
+
+fn labels(buttons: [usize; 9]) -> [usize; 9] {
+    buttons.map(|button| PHONE_BUTTON_LABELS[button])
+}
+
+/// Where the switchboard's number leads depends on the lamp's light.
+fn switchboard(solutions: &Solutions, light: Option<LightbulbColor>) -> Vec<Entry> {
+    let number = labels(solutions.phone_number());
+    match light {
+        Some(LightbulbColor::Red) => vec![Entry {
+            name: "save_universe",
+            number,
+            dialogue: &SAVE_UNIVERSE,
+        }],
+        Some(LightbulbColor::Green) => {
+            let mut backwards = number;
+            backwards.reverse();
+            vec![
+                Entry {
+                    name: "switchboard_green",
+                    number,
+                    dialogue: &SWITCHBOARD_GREEN,
+                },
+                Entry {
+                    name: "other_side",
+                    number: backwards,
+                    dialogue: &OTHER_SIDE,
+                },
+            ]
+        }
+        None => vec![],
+    }
+}
+
+/// The entry for `number`, given as the digits on the buttons, under the
+/// lamp's current `light`.
+pub fn lookup(
+    solutions: &Solutions,
+    light: Option<LightbulbColor>,
+    number: &[usize],
+) -> Option<Entry> {
+    switchboard(solutions, light)
+        .into_iter()
+        .chain([
+            // Dialing the broadcast as it is read out reaches the station itself.
+            Entry {
+                name: "numbers_station",
+                number: labels(BROADCAST_DIGITS),
+                dialogue: &NUMBERS_STATION,
+            },
+            Entry {
+                name: "operator",
+                number: [1; 9],
+                dialogue: &OPERATOR,
+            },
+        ])
+        .find(|entry| entry.number == number)
+}
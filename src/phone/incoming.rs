@@ -0,0 +1,131 @@
+//! Calls the phone receives on its own, either some time into a universe or
+//! when something happens in it. Calls nobody picks up are kept, and play back
+//! the next time the handset is lifted.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+
+use super::dialogue::{Dialogue, Line, Node};
+use super::tones::PhoneTones;
+use super::{Entry, LineState, PhoneLine};
+use crate::components::UpdateSet;
+use crate::mixer::SfxChannel;
+use crate::player::ResetUniverse;
+use crate::right_speaker::RightSpeakerDestroyed;
+
+/// How long the phone rings before the call counts as missed.
+pub const RING_TIMEOUT: Duration = Duration::from_secs(24);
+
+enum Trigger {
+    /// Time since the current universe started.
+    After(Duration),
+    SpeakerDestroyed,
+}
+
+struct ScheduledCall {
+    caller: Entry,
+    trigger: Trigger,
+}
+
+const STRANGER: Dialogue = Dialogue(&[Node::says(&[
+    Line::new("HELLO?", 3),
+    Line::new("IS ANYONE THERE?", 3),
+    Line::new("...", 3),
+    Line::new("IT'S LATER THAN", 2),
+    Line::new("YOU THINK.", 3),
+])]);
+
+const SPEAKER_COMPLAINT: Dialogue = Dialogue(&[Node::says(&[
+    Line::new("SWITCHBOARD.", 2),
+    Line::new("WE HEARD THAT.", 3),
+    Line::new("THAT SPEAKER ISN'T", 2),
+    Line::new("COMING BACK. NOT IN", 2),
+    Line::new("THIS UNIVERSE.", 3),
+    Line::new("REMEMBER WHAT IT", 2),
+    Line::new("TOLD YOU.", 3),
+])]);
+
+const SCHEDULE: &[ScheduledCall] = &[
+    ScheduledCall {
+        caller: Entry {
+            name: "stranger",
+            number: [4, 4, 4, 9, 1, 7, 2, 2, 6],
+            dialogue: &STRANGER,
+        },
+        trigger: Trigger::After(Duration::from_secs(150)),
+    },
+    ScheduledCall {
+        caller: Entry {
+            name: "speaker_complaint",
+            number: [3, 3, 3, 6, 6, 6, 9, 9, 9],
+            dialogue: &SPEAKER_COMPLAINT,
+        },
+        trigger: Trigger::SpeakerDestroyed,
+    },
+];
+
+/// Which calls this universe has placed so far.
+#[derive(Resource, Default)]
+struct CallSchedule {
+    universe_started: Duration,
+    placed: HashSet<&'static str>,
+}
+
+fn on_reset_universe(
+    time: Res<Time>,
+    mut schedule: ResMut<CallSchedule>,
+    mut reset_universe: EventReader<ResetUniverse>,
+) {
+    for _ in reset_universe.read() {
+        schedule.universe_started = time.elapsed();
+        schedule.placed.clear();
+    }
+}
+
+fn place_calls(
+    time: Res<Time>,
+    audio: Res<AudioChannel<SfxChannel>>,
+    tones: Res<PhoneTones>,
+    mut instances: ResMut<Assets<AudioInstance>>,
+    mut schedule: ResMut<CallSchedule>,
+    mut phone: ResMut<PhoneLine>,
+    mut right_speaker_destroyed: EventReader<RightSpeakerDestroyed>,
+) {
+    let speaker_destroyed = right_speaker_destroyed.read().count() > 0;
+    let universe_time = time.elapsed() - schedule.universe_started;
+
+    for call in SCHEDULE {
+        let due = match call.trigger {
+            Trigger::After(delay) => universe_time >= delay,
+            Trigger::SpeakerDestroyed => speaker_destroyed,
+        };
+        if !due || !schedule.placed.insert(call.caller.name) {
+            continue;
+        }
+
+        // There's no call waiting, so anything that comes in while the line
+        // is in use goes straight to the missed calls.
+        if matches!(phone.state, LineState::OnHook) {
+            phone.state = LineState::Incoming {
+                caller: call.caller,
+                timer: Timer::new(RING_TIMEOUT, TimerMode::Once),
+            };
+            phone.loop_tone(&audio, &mut instances, &tones.ring);
+        } else {
+            phone.missed.push(call.caller);
+        }
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<CallSchedule>();
+    app.add_systems(
+        Update,
+        (on_reset_universe, place_calls)
+            .chain()
+            .in_set(UpdateSet::PostScene),
+    );
+}
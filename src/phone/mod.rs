@@ -0,0 +1,324 @@
+//! The desk phone's line: the handset, the keypad tones, where a dialed
+//! number leads, calls coming in and the conversation once either picks up. The phone scene only
+//! sends [`PhoneInput`] and shows what [`PhoneLine`] is doing.
+
+use std::time::Duration;
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+use rand::Rng;
+
+mod dialogue;
+mod directory;
+mod incoming;
+mod tones;
+
+use dialogue::Line;
+pub use directory::Entry;
+#[cfg(test)]
+pub use incoming::RING_TIMEOUT;
+use tones::PhoneTones;
+
+use crate::components::{GameRng, UpdateSet};
+use crate::event_log::GameplayEvent;
+use crate::gamedata::PHONE_BUTTON_LABELS;
+use crate::mixer::SfxChannel;
+use crate::player::Player;
+use crate::progress::Action;
+use crate::solutions::Solutions;
+
+pub const NUMBER_LENGTH: usize = 9;
+/// How long a number that picks up rings first, one full ring cycle.
+pub const ANSWER_DELAY: Duration = Duration::from_secs(6);
+
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhoneInput {
+    PickUp,
+    HangUp,
+    /// A keypad button, by position rather than by the digit printed on it.
+    Press(usize),
+}
+
+/// Sent when a conversation performs an action.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallAction(pub Action);
+
+#[derive(Default)]
+pub enum LineState {
+    #[default]
+    OnHook,
+    /// Still on the hook, but ringing.
+    Incoming {
+        caller: Entry,
+        timer: Timer,
+    },
+    Dialing,
+    /// Wrong numbers never pick up.
+    Ringing {
+        callee: Option<Entry>,
+        timer: Timer,
+    },
+    Busy,
+    /// Waits for a choice after the last line of a node that has any.
+    Connected {
+        callee: Entry,
+        node: usize,
+        line: usize,
+        timer: Timer,
+    },
+    /// The other end has hung up.
+    Disconnected,
+}
+
+#[derive(Resource, Default)]
+pub struct PhoneLine {
+    pub state: LineState,
+    /// Oldest first.
+    pub missed: Vec<Entry>,
+    tone: Option<Handle<AudioInstance>>,
+    recording: Option<Handle<AudioInstance>>,
+}
+
+impl PhoneLine {
+    /// What the phone's display reads underneath the dialed number.
+    pub fn status(&self) -> String {
+        match &self.state {
+            LineState::OnHook if !self.missed.is_empty() => {
+                format!("{} MISSED", self.missed.len())
+            }
+            LineState::OnHook | LineState::Dialing => String::new(),
+            LineState::Incoming { .. } => "INCOMING".to_string(),
+            LineState::Ringing { .. } => "RINGING".to_string(),
+            LineState::Busy => "BUSY".to_string(),
+            LineState::Connected {
+                callee, node, line, ..
+            } => callee.dialogue.0[*node].lines[*line].text.to_string(),
+            LineState::Disconnected => "CALL ENDED".to_string(),
+        }
+    }
+
+    /// The number of whoever is calling in.
+    pub fn caller_id(&self) -> Option<[usize; 9]> {
+        match &self.state {
+            LineState::Incoming { caller, .. } => Some(caller.number),
+            _ => None,
+        }
+    }
+
+    pub fn off_hook(&self) -> bool {
+        !matches!(self.state, LineState::OnHook | LineState::Incoming { .. })
+    }
+
+    /// Whether the desk should show that the phone wants attention.
+    pub fn needs_attention(&self) -> bool {
+        matches!(self.state, LineState::Incoming { .. }) || !self.missed.is_empty()
+    }
+
+    fn stop(&mut self, instances: &mut Assets<AudioInstance>) {
+        for handle in [self.tone.take(), self.recording.take()]
+            .into_iter()
+            .flatten()
+        {
+            if let Some(instance) = instances.get_mut(&handle) {
+                instance.stop(AudioTween::default());
+            }
+        }
+    }
+
+    fn loop_tone(
+        &mut self,
+        audio: &AudioChannel<SfxChannel>,
+        instances: &mut Assets<AudioInstance>,
+        tone: &Handle<AudioSource>,
+    ) {
+        self.stop(instances);
+        self.tone = Some(audio.play(tone.clone()).looped().handle());
+    }
+}
+
+/// What a conversation needs to move on.
+#[derive(SystemParam)]
+struct Call<'w> {
+    audio: Res<'w, AudioChannel<SfxChannel>>,
+    asset_server: Res<'w, AssetServer>,
+    instances: ResMut<'w, Assets<AudioInstance>>,
+    player: ResMut<'w, Player>,
+    call_actions: EventWriter<'w, CallAction>,
+}
+
+impl Call<'_> {
+    fn enter(&mut self, phone: &mut PhoneLine, callee: Entry, node: usize) {
+        let lines = callee.dialogue.0[node].lines;
+        phone.state = LineState::Connected {
+            callee,
+            node,
+            line: 0,
+            timer: Timer::new(lines[0].duration, TimerMode::Once),
+        };
+        self.say(phone, &lines[0]);
+
+        if let Some(action) = callee.dialogue.0[node].action {
+            if self.player.flags.perform(action) {
+                self.call_actions.send(CallAction(action));
+            }
+        }
+    }
+
+    fn say(&mut self, phone: &mut PhoneLine, line: &Line) {
+        if let Some(clip) = line.clip {
+            phone.stop(&mut self.instances);
+            let clip = self.asset_server.load(clip);
+            phone.recording = Some(self.audio.play(clip).handle());
+        }
+    }
+}
+
+fn handle_input(
+    mut phone: ResMut<PhoneLine>,
+    mut call: Call,
+    mut rng: ResMut<GameRng>,
+    solutions: Res<Solutions>,
+    tones: Res<PhoneTones>,
+    mut phone_input: EventReader<PhoneInput>,
+    mut gameplay_events: EventWriter<GameplayEvent>,
+) {
+    let phone = phone.as_mut();
+    for input in phone_input.read() {
+        match *input {
+            PhoneInput::PickUp if !phone.off_hook() => {
+                call.player.dialed_numbers.clear();
+                let caller = match std::mem::take(&mut phone.state) {
+                    LineState::Incoming { caller, .. } => Some(caller),
+                    _ if !phone.missed.is_empty() => Some(phone.missed.remove(0)),
+                    _ => None,
+                };
+                if let Some(caller) = caller {
+                    phone.stop(&mut call.instances);
+                    gameplay_events.send(GameplayEvent::CallConnected(caller.name));
+                    call.enter(phone, caller, 0);
+                } else {
+                    phone.state = LineState::Dialing;
+                    phone.loop_tone(&call.audio, &mut call.instances, &tones.dial);
+                }
+            }
+            // Walking away from a ringing phone doesn't answer it either.
+            PhoneInput::HangUp if phone.off_hook() => {
+                phone.stop(&mut call.instances);
+                phone.state = LineState::OnHook;
+            }
+            PhoneInput::Press(button) if phone.off_hook() => {
+                let digit = PHONE_BUTTON_LABELS[button];
+                call.audio.play(tones.dtmf(digit));
+                match &phone.state {
+                    LineState::Dialing => (),
+                    LineState::Connected { callee, node, .. } => {
+                        let callee = *callee;
+                        if let Some(next) = callee.dialogue.0[*node].next(digit) {
+                            gameplay_events.send(GameplayEvent::DialogueChoice {
+                                callee: callee.name,
+                                digit,
+                            });
+                            call.enter(phone, callee, next);
+                        }
+                        continue;
+                    }
+                    _ => continue,
+                }
+
+                // The dial tone stops at the first digit.
+                phone.stop(&mut call.instances);
+                call.player.dialed_numbers.push(button);
+                gameplay_events.send(GameplayEvent::PhoneDigitDialed(button));
+                if call.player.dialed_numbers.len() < NUMBER_LENGTH {
+                    continue;
+                }
+
+                let number: Vec<usize> = call
+                    .player
+                    .dialed_numbers
+                    .iter()
+                    .map(|button| PHONE_BUTTON_LABELS[*button])
+                    .collect();
+                let light = call.player.lamp_light();
+                let callee = directory::lookup(&solutions, light, &number);
+                if callee.is_none() && rng.0.gen_bool(0.5) {
+                    phone.state = LineState::Busy;
+                    phone.loop_tone(&call.audio, &mut call.instances, &tones.busy);
+                } else {
+                    phone.state = LineState::Ringing {
+                        callee,
+                        timer: Timer::new(ANSWER_DELAY, TimerMode::Once),
+                    };
+                    phone.loop_tone(&call.audio, &mut call.instances, &tones.ringback);
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+fn update_call(
+    time: Res<Time>,
+    mut phone: ResMut<PhoneLine>,
+    mut call: Call,
+    mut gameplay_events: EventWriter<GameplayEvent>,
+) {
+    let phone = phone.as_mut();
+    match &mut phone.state {
+        LineState::Incoming { caller, timer } => {
+            if !timer.tick(time.delta()).finished() {
+                return;
+            }
+            let caller = *caller;
+            phone.stop(&mut call.instances);
+            phone.missed.push(caller);
+            phone.state = LineState::OnHook;
+        }
+        LineState::Ringing {
+            callee: Some(callee),
+            timer,
+        } => {
+            if !timer.tick(time.delta()).finished() {
+                return;
+            }
+            let callee = *callee;
+            phone.stop(&mut call.instances);
+            gameplay_events.send(GameplayEvent::CallConnected(callee.name));
+            call.enter(phone, callee, 0);
+        }
+        LineState::Connected {
+            callee,
+            node,
+            line,
+            timer,
+        } => {
+            if !timer.tick(time.delta()).finished() {
+                return;
+            }
+            let node = &callee.dialogue.0[*node];
+            if let Some(next) = node.lines.get(*line + 1) {
+                *line += 1;
+                *timer = Timer::new(next.duration, TimerMode::Once);
+                call.say(phone, next);
+            } else if node.choices.is_empty() {
+                phone.stop(&mut call.instances);
+                phone.state = LineState::Disconnected;
+            }
+        }
+        _ => (),
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_event::<PhoneInput>();
+    app.add_event::<CallAction>();
+    app.init_resource::<PhoneLine>();
+    app.add_plugins((tones::plugin, incoming::plugin));
+    app.add_systems(
+        Update,
+        (handle_input, update_call)
+            .chain()
+            .in_set(UpdateSet::PostScene),
+    );
+}
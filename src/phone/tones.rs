@@ -0,0 +1,128 @@
+//! Call progress and keypad tones, synthesized at startup instead of shipped
+//! as recordings.
+
+use std::f32::consts::TAU;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+
+const SAMPLE_RATE: u32 = 44_100;
+const AMPLITUDE: f32 = 0.2;
+// Short ramps at both ends of a tone that stops, so that it doesn't click.
+const FADE: Duration = Duration::from_millis(5);
+
+const DTMF_ROWS: [f32; 3] = [697.0, 770.0, 852.0];
+const DTMF_COLUMNS: [f32; 3] = [1209.0, 1336.0, 1477.0];
+const DTMF_LENGTH: Duration = Duration::from_millis(150);
+const DTMF_GAP: Duration = Duration::from_millis(50);
+// A bell ringer hammers back and forth this many times a second.
+const RINGER_RATE: f32 = 20.0;
+
+/// The tones a phone line plays, as North American phones would.
+#[derive(Resource)]
+pub struct PhoneTones {
+    /// Indexed by the digit on the key, 1 to 9.
+    dtmf: [Handle<AudioSource>; 9],
+    /// Continuous while waiting for the first digit.
+    pub dial: Handle<AudioSource>,
+    /// One ring cycle, heard while the other end is ringing.
+    pub ringback: Handle<AudioSource>,
+    /// One busy cycle.
+    pub busy: Handle<AudioSource>,
+    /// One ring cycle of the phone on the desk itself.
+    pub ring: Handle<AudioSource>,
+}
+
+impl PhoneTones {
+    pub fn dtmf(&self, digit: usize) -> Handle<AudioSource> {
+        self.dtmf[digit - 1].clone()
+    }
+}
+
+/// `frequencies` mixed together for `on`, then silence for `off`. Without any
+/// silence the tone is meant to loop, so it isn't faded.
+fn tone(frequencies: &[f32], on: Duration, off: Duration) -> AudioSource {
+    let seconds_to_frames =
+        |duration: Duration| (duration.as_secs_f32() * SAMPLE_RATE as f32).round() as usize;
+    let on_frames = seconds_to_frames(on);
+    let fade_frames = if off.is_zero() {
+        0
+    } else {
+        seconds_to_frames(FADE)
+    };
+    let frames: Arc<[Frame]> = (0..on_frames + seconds_to_frames(off))
+        .map(|i| {
+            if i >= on_frames {
+                return Frame::ZERO;
+            }
+            let t = i as f32 / SAMPLE_RATE as f32;
+            let edge = i.min(on_frames - i);
+            let fade = if edge < fade_frames {
+                edge as f32 / fade_frames as f32
+            } else {
+                1.0
+            };
+            let sample: f32 = frequencies.iter().map(|f| (TAU * f * t).sin()).sum();
+            Frame::from_mono(sample * fade * AMPLITUDE / frequencies.len() as f32)
+        })
+        .collect();
+
+    AudioSource {
+        sound: StaticSoundData {
+            sample_rate: SAMPLE_RATE,
+            frames,
+            settings: StaticSoundSettings::default(),
+        },
+    }
+}
+
+/// Chops `source` up as a ringer's bells would.
+fn trill(mut source: AudioSource) -> AudioSource {
+    source.sound.frames = source
+        .sound
+        .frames
+        .iter()
+        .enumerate()
+        .map(|(i, frame)| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            *frame * (TAU * RINGER_RATE * t).sin().abs()
+        })
+        .collect();
+    source
+}
+
+fn setup(mut commands: Commands, mut sources: ResMut<Assets<AudioSource>>) {
+    let dtmf = std::array::from_fn(|i| {
+        let pair = [DTMF_ROWS[i / 3], DTMF_COLUMNS[i % 3]];
+        sources.add(tone(&pair, DTMF_LENGTH, DTMF_GAP))
+    });
+    commands.insert_resource(PhoneTones {
+        dtmf,
+        dial: sources.add(tone(
+            &[350.0, 440.0],
+            Duration::from_secs(1),
+            Duration::ZERO,
+        )),
+        ringback: sources.add(tone(
+            &[440.0, 480.0],
+            Duration::from_secs(2),
+            Duration::from_secs(4),
+        )),
+        busy: sources.add(tone(
+            &[480.0, 620.0],
+            Duration::from_millis(500),
+            Duration::from_millis(500),
+        )),
+        ring: sources.add(trill(tone(
+            &[1000.0, 1250.0],
+            Duration::from_secs(2),
+            Duration::from_secs(4),
+        ))),
+    });
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(Startup, setup);
+}
@@ -32,6 +32,8 @@ use bevy::{
     },
 };
 
+use crate::settings::Settings;
+
 /// It is generally encouraged to set up post processing effects as a plugin
 pub struct PixelatePlugin;
 
@@ -102,7 +104,7 @@ impl Plugin for PixelatePlugin {
 }
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
-struct PostProcessLabel;
+pub struct PostProcessLabel;
 
 // The post process node used for the render graph
 #[derive(Default)]
@@ -307,12 +309,26 @@ fn sawtooth_wave(time: f32) -> f32 {
 }
 
 // Change the intensity over time to show that the effect is controlled from the main world
-fn update_settings(mut settings: Query<&mut PixelateSettings>, time: Res<Time>) {
+fn update_settings(
+    mut settings: Query<&mut PixelateSettings>,
+    options: Res<Settings>,
+    time: Res<Time>,
+) {
     for mut setting in &mut settings {
         let intensity_scale = 3.0;
         let intensity_base = 5.0;
         let intensity_speed = 1.0 / 240.0;
 
+        // A block size of 1 leaves every pixel as it is.
+        if !options.pixelation {
+            setting.block_size = 1.0;
+            continue;
+        }
+        if options.reduced_motion {
+            setting.block_size = intensity_base;
+            continue;
+        }
+
         let mut intensity = sawtooth_wave(intensity_speed * time.elapsed_seconds());
 
         intensity *= intensity_scale;
@@ -1,7 +1,8 @@
 use crate::camera::{HORIZONTAL_RESOLUTION, VERTICAL_RESOLUTION};
 use crate::components::{ClickableArea, ClickableScene, ClickableShape, Keyboard, UpdateSet};
-use crate::gamedata::{debug_text_style, RenderLayer, SceneId, ScenePlayerControl};
+use crate::gamedata::{debug_text_style, LetterPuzzle, RenderLayer, SceneId, ScenePlayerControl};
 use crate::input::MousePosition;
+use crate::progress::{Flag, Flags};
 use bevy::prelude::*;
 use bevy::sprite::Anchor;
 use std::fmt::Write;
@@ -33,26 +34,29 @@ pub struct Background0;
 #[derive(Component)]
 pub struct Background1;
 
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum LightbulbColor {
     Green,
     Red,
 }
 
+impl LightbulbColor {
+    /// How the scene art lit by this color is named.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Green => "green",
+            Self::Red => "red",
+        }
+    }
+}
+
 #[derive(Resource)]
 pub struct Player {
     pub scene: SceneState,
     pub behind_puzzle_state: [usize; 6],
     pub keypad_drawer_puzzle_state: [usize; 3],
-    pub lightbulb_unlock: Option<LightbulbColor>,
-    pub installed_lightbulb: Option<LightbulbColor>,
-    pub right_speaker_broken: bool,
-    pub has_surge_protector: bool,
-    pub has_installed_surge_protector: bool,
-    pub has_morse_code_translator: bool,
-    pub has_installed_morse_code_translator: bool,
-    pub has_key: bool,
-    pub opened_key_drawer: bool,
+    pub door_puzzle_state: [usize; 3],
+    pub flags: Flags,
     pub dialed_numbers: Vec<usize>,
 }
 
@@ -62,18 +66,62 @@ impl Player {
             scene: SceneState::Active(SceneId::Desk),
             behind_puzzle_state: [0; 6],
             keypad_drawer_puzzle_state: [0; 3],
-            lightbulb_unlock: None,
-            installed_lightbulb: None,
-            right_speaker_broken: false,
-            has_surge_protector: false,
-            has_installed_surge_protector: false,
-            has_morse_code_translator: false,
-            has_installed_morse_code_translator: false,
-            has_key: false,
-            opened_key_drawer: false,
+            door_puzzle_state: [0; 3],
+            flags: Flags::default(),
             dialed_numbers: vec![],
         }
     }
+
+    /// Starts a fresh run from the desk, wherever this one had got to.
+    pub fn restart(&mut self) {
+        let scene = match self.scene {
+            SceneState::Active(scene)
+            | SceneState::Transitioning(_, scene, _)
+            | SceneState::ForceTransition(_, scene) => scene,
+        };
+        *self = Self::new();
+        self.scene = SceneState::ForceTransition(scene, SceneId::Desk);
+    }
+
+    pub fn letter_wheels(&self, puzzle: LetterPuzzle) -> &[usize] {
+        match puzzle {
+            LetterPuzzle::KeypadDrawer => &self.keypad_drawer_puzzle_state,
+            LetterPuzzle::Behind => &self.behind_puzzle_state,
+            LetterPuzzle::Door => &self.door_puzzle_state,
+        }
+    }
+
+    pub fn letter_wheels_mut(&mut self, puzzle: LetterPuzzle) -> &mut [usize] {
+        match puzzle {
+            LetterPuzzle::KeypadDrawer => &mut self.keypad_drawer_puzzle_state,
+            LetterPuzzle::Behind => &mut self.behind_puzzle_state,
+            LetterPuzzle::Door => &mut self.door_puzzle_state,
+        }
+    }
+
+    pub fn has(&self, flag: Flag) -> bool {
+        self.flags.contains(flag)
+    }
+
+    pub fn lightbulb_unlock(&self) -> Option<LightbulbColor> {
+        Self::lightbulb(&self.flags, Flag::bulb_unlocked)
+    }
+
+    pub fn installed_lightbulb(&self) -> Option<LightbulbColor> {
+        Self::lightbulb(&self.flags, Flag::bulb_installed)
+    }
+
+    /// The color the lamp lights the room in, if it is on and has a bulb.
+    pub fn lamp_light(&self) -> Option<LightbulbColor> {
+        self.installed_lightbulb()
+            .filter(|_| self.has(Flag::LampOn))
+    }
+
+    fn lightbulb(flags: &Flags, flag: fn(LightbulbColor) -> Flag) -> Option<LightbulbColor> {
+        [LightbulbColor::Red, LightbulbColor::Green]
+            .into_iter()
+            .find(|color| flags.contains(flag(*color)))
+    }
 }
 
 #[derive(Component)]
@@ -101,7 +149,7 @@ fn scene_transition_system(
     }
 }
 
-fn keyboard_input_system(
+pub fn keyboard_input_system(
     keyboard: Keyboard,
     mut player: ResMut<Player>,
     mut unload_scene: EventWriter<UnloadScene>,
@@ -0,0 +1,499 @@
+//! Puzzle progression as data: what each action needs and what it changes.
+//!
+//! Scenes go through [`Flags::perform`] instead of poking at the state
+//! directly, so the same rules drive the game and [`analyze`], which walks
+//! every reachable state looking for unreachable endings and softlocks.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+
+use crate::player::LightbulbColor;
+
+pub trait Bit: Copy {
+    fn bit(self) -> u32;
+}
+
+#[derive(Debug)]
+pub struct BitSet<T>(u32, PhantomData<T>);
+
+impl<T> Clone for BitSet<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for BitSet<T> {}
+
+impl<T> PartialEq for BitSet<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T> Eq for BitSet<T> {}
+
+impl<T> std::hash::Hash for BitSet<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl<T> Default for BitSet<T> {
+    fn default() -> Self {
+        Self(0, PhantomData)
+    }
+}
+
+impl<T: Bit> BitSet<T> {
+    pub fn contains(&self, value: T) -> bool {
+        self.0 & value.bit() != 0
+    }
+
+    pub fn contains_all(&self, values: &[T]) -> bool {
+        values.iter().all(|v| self.contains(*v))
+    }
+
+    pub fn contains_any(&self, values: &[T]) -> bool {
+        values.iter().any(|v| self.contains(*v))
+    }
+
+    pub fn insert(&mut self, value: T) {
+        self.0 |= value.bit();
+    }
+
+    pub fn remove(&mut self, value: T) {
+        self.0 &= !value.bit();
+    }
+}
+
+/// Game state that lasts until the universe is reset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Flag {
+    HasKey,
+    OpenedKeyDrawer,
+    HasMorseCodeTranslator,
+    InstalledMorseCodeTranslator,
+    RightSpeakerBroken,
+    OpenedKeypadDrawer,
+    HasSurgeProtector,
+    InstalledSurgeProtector,
+    RedBulbUnlocked,
+    GreenBulbUnlocked,
+    RedBulbInstalled,
+    GreenBulbInstalled,
+    LampOn,
+    UniverseSaved,
+    SaveSwitchUnlocked,
+    SaveSwitchPulled,
+    UniverseAbandoned,
+    UniverseTraded,
+    HasFlashlight,
+    HeardKnocks,
+    DoorUnlocked,
+    Escaped,
+}
+
+impl Bit for Flag {
+    fn bit(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+impl Flag {
+    pub const fn bulb_unlocked(color: LightbulbColor) -> Self {
+        match color {
+            LightbulbColor::Red => Self::RedBulbUnlocked,
+            LightbulbColor::Green => Self::GreenBulbUnlocked,
+        }
+    }
+
+    pub const fn bulb_installed(color: LightbulbColor) -> Self {
+        match color {
+            LightbulbColor::Red => Self::RedBulbInstalled,
+            LightbulbColor::Green => Self::GreenBulbInstalled,
+        }
+    }
+}
+
+/// What the player (not the game) knows. Only tracked by the analysis; in game
+/// the puzzles themselves check for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Clue {
+    KeypadCode,
+    BehindCode,
+    PhoneNumber,
+    KnockCode,
+}
+
+pub const ALL_CLUES: [Clue; 4] = [
+    Clue::KeypadCode,
+    Clue::BehindCode,
+    Clue::PhoneNumber,
+    Clue::KnockCode,
+];
+
+impl Clue {
+    /// Whether the answer stays the same for the whole run. The rest are
+    /// picked again for every universe, so knowing them doesn't survive a
+    /// reset.
+    pub const fn lasts_the_run(self) -> bool {
+        matches!(self, Self::KeypadCode | Self::KnockCode)
+    }
+}
+
+impl Bit for Clue {
+    fn bit(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+pub type Flags = BitSet<Flag>;
+pub type Clues = BitSet<Clue>;
+
+impl Clues {
+    /// What's still worth knowing in the next universe.
+    pub fn after_reset(mut self) -> Self {
+        for clue in ALL_CLUES {
+            if !clue.lasts_the_run() {
+                self.remove(clue);
+            }
+        }
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    TakeKey,
+    UnlockKeyDrawer,
+    TakeMorseCodeTranslator,
+    TakeFlashlight,
+    InstallMorseCodeTranslator,
+    ListenToMorse,
+    ListenToNumbers,
+    ListenToKnocks,
+    OpenKeypadDrawer,
+    TakeSurgeProtector,
+    InstallSurgeProtector,
+    WinTv(LightbulbColor),
+    InstallLightbulb(LightbulbColor),
+    PullLampSwitch,
+    DialPhone,
+    AbandonUniverse,
+    TradeUniverse,
+    UnlockSaveSwitch,
+    PullSaveSwitch,
+    UnlockDoor,
+    OpenDoor,
+}
+
+pub struct Rule {
+    pub action: Action,
+    pub requires: &'static [Flag],
+    pub forbids: &'static [Flag],
+    pub needs_clues: &'static [Clue],
+    pub sets: &'static [Flag],
+    pub clears: &'static [Flag],
+    pub reveals: &'static [Clue],
+}
+
+impl Rule {
+    const fn new(action: Action) -> Self {
+        Self {
+            action,
+            requires: &[],
+            forbids: &[],
+            needs_clues: &[],
+            sets: &[],
+            clears: &[],
+            reveals: &[],
+        }
+    }
+
+    const fn requires(self, requires: &'static [Flag]) -> Self {
+        Self { requires, ..self }
+    }
+
+    const fn forbids(self, forbids: &'static [Flag]) -> Self {
+        Self { forbids, ..self }
+    }
+
+    const fn needs_clues(self, needs_clues: &'static [Clue]) -> Self {
+        Self {
+            needs_clues,
+            ..self
+        }
+    }
+
+    const fn sets(self, sets: &'static [Flag]) -> Self {
+        Self { sets, ..self }
+    }
+
+    const fn clears(self, clears: &'static [Flag]) -> Self {
+        Self { clears, ..self }
+    }
+
+    const fn reveals(self, reveals: &'static [Clue]) -> Self {
+        Self { reveals, ..self }
+    }
+
+    pub fn is_allowed(&self, flags: &Flags) -> bool {
+        flags.contains_all(self.requires) && !flags.contains_any(self.forbids)
+    }
+
+    fn apply(&self, flags: &mut Flags) {
+        for flag in self.sets {
+            flags.insert(*flag);
+        }
+        for flag in self.clears {
+            flags.remove(*flag);
+        }
+    }
+}
+
+use Flag::*;
+
+// The first allowed rule for an action wins, so more specific rules go first.
+pub const RULES: &[Rule] = &[
+    Rule::new(Action::TakeKey)
+        .forbids(&[HasKey])
+        .sets(&[HasKey]),
+    Rule::new(Action::UnlockKeyDrawer)
+        .requires(&[HasKey])
+        .forbids(&[OpenedKeyDrawer])
+        .sets(&[OpenedKeyDrawer]),
+    Rule::new(Action::TakeMorseCodeTranslator)
+        .requires(&[OpenedKeyDrawer])
+        .forbids(&[HasMorseCodeTranslator])
+        .sets(&[HasMorseCodeTranslator]),
+    Rule::new(Action::TakeFlashlight)
+        .requires(&[OpenedKeyDrawer])
+        .forbids(&[HasFlashlight])
+        .sets(&[HasFlashlight]),
+    Rule::new(Action::InstallMorseCodeTranslator)
+        .requires(&[HasMorseCodeTranslator, InstalledSurgeProtector])
+        .forbids(&[InstalledMorseCodeTranslator])
+        .sets(&[InstalledMorseCodeTranslator]),
+    // Without the surge protector the module blows the right speaker.
+    Rule::new(Action::InstallMorseCodeTranslator)
+        .requires(&[HasMorseCodeTranslator])
+        .forbids(&[InstalledMorseCodeTranslator])
+        .sets(&[InstalledMorseCodeTranslator, RightSpeakerBroken]),
+    Rule::new(Action::ListenToMorse)
+        .requires(&[InstalledMorseCodeTranslator, RightSpeakerBroken])
+        .reveals(&[Clue::KeypadCode]),
+    Rule::new(Action::ListenToMorse)
+        .requires(&[InstalledMorseCodeTranslator])
+        .reveals(&[Clue::BehindCode]),
+    Rule::new(Action::ListenToNumbers).reveals(&[Clue::PhoneNumber]),
+    // Someone starts knocking once the radio is on the air.
+    Rule::new(Action::ListenToKnocks)
+        .requires(&[InstalledMorseCodeTranslator])
+        .sets(&[HeardKnocks])
+        .reveals(&[Clue::KnockCode]),
+    Rule::new(Action::OpenKeypadDrawer)
+        .forbids(&[OpenedKeypadDrawer])
+        .needs_clues(&[Clue::KeypadCode])
+        .sets(&[OpenedKeypadDrawer]),
+    Rule::new(Action::TakeSurgeProtector)
+        .requires(&[OpenedKeypadDrawer])
+        .forbids(&[HasSurgeProtector])
+        .sets(&[HasSurgeProtector]),
+    Rule::new(Action::InstallSurgeProtector)
+        .requires(&[HasSurgeProtector])
+        .forbids(&[InstalledSurgeProtector])
+        .sets(&[InstalledSurgeProtector]),
+    Rule::new(Action::WinTv(LightbulbColor::Red))
+        .sets(&[RedBulbUnlocked])
+        .clears(&[GreenBulbUnlocked]),
+    Rule::new(Action::WinTv(LightbulbColor::Green))
+        .sets(&[GreenBulbUnlocked])
+        .clears(&[RedBulbUnlocked]),
+    // Putting in one bulb takes the other out.
+    Rule::new(Action::InstallLightbulb(LightbulbColor::Red))
+        .requires(&[RedBulbUnlocked])
+        .forbids(&[RedBulbInstalled])
+        .sets(&[RedBulbInstalled])
+        .clears(&[GreenBulbInstalled]),
+    Rule::new(Action::InstallLightbulb(LightbulbColor::Green))
+        .requires(&[GreenBulbUnlocked])
+        .forbids(&[GreenBulbInstalled])
+        .sets(&[GreenBulbInstalled])
+        .clears(&[RedBulbInstalled]),
+    Rule::new(Action::PullLampSwitch)
+        .requires(&[LampOn])
+        .clears(&[LampOn]),
+    Rule::new(Action::PullLampSwitch).sets(&[LampOn]),
+    Rule::new(Action::DialPhone)
+        .requires(&[RedBulbInstalled, LampOn])
+        .forbids(&[UniverseSaved, UniverseAbandoned])
+        .needs_clues(&[Clue::PhoneNumber])
+        .sets(&[UniverseSaved]),
+    // Told the switchboard to let this universe go instead.
+    Rule::new(Action::AbandonUniverse)
+        .requires(&[RedBulbInstalled, LampOn])
+        .forbids(&[UniverseSaved, UniverseAbandoned])
+        .needs_clues(&[Clue::PhoneNumber])
+        .sets(&[UniverseAbandoned]),
+    // Under green light the same number, dialed backwards, reaches the other
+    // side instead.
+    Rule::new(Action::TradeUniverse)
+        .requires(&[GreenBulbInstalled, LampOn])
+        .forbids(&[UniverseTraded])
+        .needs_clues(&[Clue::PhoneNumber])
+        .sets(&[UniverseTraded]),
+    Rule::new(Action::UnlockSaveSwitch)
+        .forbids(&[SaveSwitchUnlocked])
+        .needs_clues(&[Clue::BehindCode])
+        .sets(&[SaveSwitchUnlocked]),
+    Rule::new(Action::PullSaveSwitch)
+        .requires(&[SaveSwitchUnlocked])
+        .forbids(&[SaveSwitchPulled])
+        .sets(&[SaveSwitchPulled]),
+    // The bolt on the other side only comes off once they've knocked.
+    Rule::new(Action::UnlockDoor)
+        .requires(&[HeardKnocks])
+        .forbids(&[DoorUnlocked])
+        .needs_clues(&[Clue::KnockCode])
+        .sets(&[DoorUnlocked]),
+    // The hallway outside is pitch black.
+    Rule::new(Action::OpenDoor)
+        .requires(&[DoorUnlocked, HasFlashlight])
+        .forbids(&[Escaped])
+        .sets(&[Escaped]),
+];
+
+pub const ENDINGS: &[Flag] = &[
+    UniverseSaved,
+    SaveSwitchPulled,
+    UniverseAbandoned,
+    UniverseTraded,
+    Escaped,
+];
+
+impl Flags {
+    /// Applies the first rule for `action` whose prerequisites hold. Returns
+    /// whether anything happened.
+    pub fn perform(&mut self, action: Action) -> bool {
+        let Some(rule) = RULES
+            .iter()
+            .find(|rule| rule.action == action && rule.is_allowed(self))
+        else {
+            return false;
+        };
+        rule.apply(self);
+        true
+    }
+}
+
+type State = (Flags, Clues);
+
+#[derive(Debug, Default)]
+pub struct Analysis {
+    pub reachable_states: usize,
+    pub unreachable_endings: Vec<Flag>,
+    /// States where nothing but resetting the universe changes anything.
+    pub dead_ends: Vec<State>,
+    /// States from which no ending can be reached without resetting.
+    pub softlocks: Vec<State>,
+}
+
+fn successors((flags, clues): State) -> Vec<State> {
+    let mut next: Vec<State> = vec![];
+    for rule in RULES {
+        if !rule.is_allowed(&flags) || !clues.contains_all(rule.needs_clues) {
+            continue;
+        }
+        let mut flags = flags;
+        let mut clues = clues;
+        rule.apply(&mut flags);
+        for clue in rule.reveals {
+            clues.insert(*clue);
+        }
+        next.push((flags, clues));
+    }
+    next
+}
+
+/// Walks every state reachable from a fresh game, including universe resets.
+pub fn analyze() -> Analysis {
+    let start: State = Default::default();
+    let mut edges: HashMap<State, Vec<State>> = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+    let mut analysis = Analysis::default();
+
+    while let Some(state) = queue.pop_front() {
+        if edges.contains_key(&state) {
+            continue;
+        }
+        let progress: Vec<State> = successors(state)
+            .into_iter()
+            .filter(|next| *next != state)
+            .collect();
+        let ended = state.0.contains_any(ENDINGS);
+        if progress.is_empty() && !ended {
+            analysis.dead_ends.push(state);
+        }
+
+        queue.extend(progress.iter().copied());
+        // Resetting leads on to universes that start out knowing more, but it
+        // doesn't count as a way out of a state: any state could take it.
+        queue.push_back((Flags::default(), state.1.after_reset()));
+        edges.insert(state, progress);
+    }
+
+    // Walk backwards from every state that has reached an ending.
+    let mut reverse: HashMap<State, Vec<State>> = HashMap::new();
+    for (from, tos) in &edges {
+        for to in tos {
+            reverse.entry(*to).or_default().push(*from);
+        }
+    }
+    let mut can_finish: HashSet<State> = HashSet::new();
+    let mut queue: VecDeque<State> = edges
+        .keys()
+        .filter(|state| state.0.contains_any(ENDINGS))
+        .copied()
+        .collect();
+    while let Some(state) = queue.pop_front() {
+        if can_finish.insert(state) {
+            queue.extend(reverse.get(&state).into_iter().flatten().copied());
+        }
+    }
+
+    analysis.reachable_states = edges.len();
+    analysis.unreachable_endings = ENDINGS
+        .iter()
+        .filter(|ending| !edges.keys().any(|state| state.0.contains(**ending)))
+        .copied()
+        .collect();
+    analysis.softlocks = edges
+        .keys()
+        .filter(|state| !can_finish.contains(*state))
+        .copied()
+        .collect();
+    analysis
+}
+
+fn report_analysis() {
+    let analysis = analyze();
+    info!(
+        "progress analysis: {} reachable states, {} dead ends",
+        analysis.reachable_states,
+        analysis.dead_ends.len()
+    );
+    for ending in &analysis.unreachable_endings {
+        warn!("ending {:?} can never be reached", ending);
+    }
+    for state in &analysis.softlocks {
+        warn!("softlock: no ending reachable from {:?}", state);
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    if cfg!(feature = "debug_state") {
+        app.add_systems(Startup, report_analysis);
+    }
+}
@@ -0,0 +1,257 @@
+//! The noise an AM receiver makes between stations, synthesized rather than
+//! played from a recording: band-limited hiss, bursts of crackle and a slow
+//! fade, all following what the dial is tuned to.
+
+use std::f64::consts::TAU;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::audio::RadioAudio;
+use crate::components::GameRng;
+use crate::gamedata::AmRadioFreq;
+use crate::mixer::{ApplyMix, Mixer, StaticChannel, Track};
+use crate::player::Player;
+use crate::progress::Flag;
+use crate::solutions;
+
+const SAMPLE_RATE: u32 = 44_100;
+// Loops of different, odd lengths, so that the repeats don't line up.
+const HISS_SECONDS: f64 = 5.3;
+const CRACKLE_SECONDS: f64 = 7.9;
+// Hiss fades into itself over this much at the loop point.
+const LOOP_OVERLAP_SECONDS: f64 = 0.05;
+// What gets through an AM receiver, in Hz.
+const LOW_CUT: f64 = 250.0;
+const HIGH_CUT: f64 = 4500.0;
+const CRACKLES_PER_SECOND: f64 = 6.0;
+const CRACKLE_DECAY_SECONDS: f64 = 0.004;
+const PEAK: f32 = 0.8;
+
+// Levels change in small steps, each one tweened over the next. The audio
+// thread only takes a handful of commands per sound at a time.
+const REFRESH: Duration = Duration::from_millis(100);
+const FADE_DEPTH: f64 = 0.35;
+const FADE_PERIODS: [f64; 2] = [11.0, 4.7];
+// Odds per refresh of a burst of crackle, and how quickly it dies down.
+const BURST_CHANCE: f64 = 0.03;
+const BURST_DECAY: f64 = 0.85;
+const MAX_BURST: f64 = 2.5;
+
+/// The synthesized loops playing on the static channel.
+#[derive(Resource)]
+pub struct RadioStatic {
+    hiss: Handle<AudioInstance>,
+    crackle: Handle<AudioInstance>,
+    /// How strongly the stations come in together, from 0 to 1.
+    pub signal: f64,
+    burst: f64,
+    panning: Option<f64>,
+    refresh: Timer,
+    rng: StdRng,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StaticLevels {
+    pub hiss: f64,
+    pub crackle: f64,
+    /// Faster playback shifts the hiss up, brightening it.
+    pub playback_rate: f64,
+}
+
+/// How the static sounds tuned to `freq` with a station coming in at
+/// `signal`, before fading and bursts.
+pub fn levels(freq: i32, signal: f64) -> StaticLevels {
+    let band = (freq - AmRadioFreq::MIN) as f64 / (AmRadioFreq::MAX - AmRadioFreq::MIN) as f64;
+    let noise = 1.0 - signal.clamp(0.0, 1.0);
+    StaticLevels {
+        // A carrier turns the receiver's gain down, and the hiss with it.
+        hiss: 0.5 + 0.5 * noise,
+        // Atmospheric crackle is worst at the bottom of the band.
+        crackle: noise * (1.0 - 0.5 * band),
+        playback_rate: 0.85 + 0.3 * band,
+    }
+}
+
+fn seconds(seconds: f64) -> usize {
+    (seconds * SAMPLE_RATE as f64) as usize
+}
+
+// The coefficient of a one-pole low-pass at `cutoff` Hz.
+fn one_pole(cutoff: f64) -> f64 {
+    1.0 - (-TAU * cutoff / SAMPLE_RATE as f64).exp()
+}
+
+fn normalize(samples: &mut [f32]) {
+    let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    if peak > 0.0 {
+        for sample in samples.iter_mut() {
+            *sample *= PEAK / peak;
+        }
+    }
+}
+
+/// White noise through a band-pass, looping without a seam.
+pub fn hiss(rng: &mut impl Rng) -> Vec<f32> {
+    let length = seconds(HISS_SECONDS);
+    let overlap = seconds(LOOP_OVERLAP_SECONDS);
+    let (high_pass, low_pass) = (one_pole(LOW_CUT), one_pole(HIGH_CUT));
+    let (mut rumble, mut band, mut hiss) = (0.0, 0.0, 0.0);
+    let mut samples: Vec<f32> = (0..length + overlap)
+        .map(|_| {
+            let white = rng.gen_range(-1.0..1.0);
+            rumble += high_pass * (white - rumble);
+            // Two low-pass stages, for a steeper roll-off than one gives.
+            band += low_pass * (white - rumble - band);
+            hiss += low_pass * (band - hiss);
+            hiss as f32
+        })
+        .collect();
+    // The tail crossfades into the head, so the end runs into the start.
+    for i in 0..overlap {
+        let t = i as f32 / overlap as f32;
+        samples[i] = samples[i] * t + samples[length + i] * (1.0 - t);
+    }
+    samples.truncate(length);
+    normalize(&mut samples);
+    samples
+}
+
+/// Sparse clicks of decaying noise, bunched up the way static discharges are.
+pub fn crackle(rng: &mut impl Rng) -> Vec<f32> {
+    let length = seconds(CRACKLE_SECONDS);
+    let decay = CRACKLE_DECAY_SECONDS * SAMPLE_RATE as f64;
+    let mut samples = vec![0.0; length];
+    let mut at = 0;
+    loop {
+        // Exponential gaps make a Poisson process, which clusters on its own.
+        let gap = -(1.0 - rng.gen::<f64>()).ln() / CRACKLES_PER_SECOND;
+        at += seconds(gap);
+        if at >= length {
+            break;
+        }
+        let amplitude = rng.gen_range(0.2..1.0f64).powi(2);
+        for i in 0..(decay * 6.0) as usize {
+            let envelope = amplitude * (-(i as f64) / decay).exp();
+            // Clicks near the end wrap around to the start of the loop.
+            samples[(at + i) % length] += (envelope * rng.gen_range(-1.0..1.0)) as f32;
+        }
+    }
+    normalize(&mut samples);
+    samples
+}
+
+fn source(samples: Vec<f32>) -> AudioSource {
+    AudioSource {
+        sound: StaticSoundData {
+            sample_rate: SAMPLE_RATE,
+            frames: samples
+                .into_iter()
+                .map(Frame::from_mono)
+                .collect::<Arc<_>>(),
+            settings: StaticSoundSettings::default(),
+        },
+    }
+}
+
+fn fade(elapsed: f64) -> f64 {
+    let swell: f64 = FADE_PERIODS
+        .iter()
+        .map(|period| (elapsed * TAU / period).sin())
+        .sum::<f64>()
+        / FADE_PERIODS.len() as f64;
+    1.0 - FADE_DEPTH * (0.5 + 0.5 * swell)
+}
+
+fn setup(
+    mut commands: Commands,
+    mut sources: ResMut<Assets<AudioSource>>,
+    channel: Res<AudioChannel<StaticChannel>>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    // Its own generator, so that the static doesn't shift what the rest of the
+    // game rolls, but seeded from the game's so that a replay sounds the same.
+    let mut rng = StdRng::from_rng(&mut game_rng.0).unwrap();
+    let hiss = sources.add(source(hiss(&mut rng)));
+    let crackle = sources.add(source(crackle(&mut rng)));
+    commands.insert_resource(RadioStatic {
+        hiss: channel.play(hiss).looped().handle(),
+        crackle: channel.play(crackle).looped().with_volume(0.0).handle(),
+        signal: 0.0,
+        burst: 1.0,
+        panning: None,
+        refresh: Timer::new(REFRESH, TimerMode::Repeating),
+        rng,
+    });
+}
+
+fn update(
+    time: Res<Time>,
+    player: Res<Player>,
+    mixer: Res<Mixer>,
+    radio: Query<&AmRadioFreq, With<RadioAudio>>,
+    mut radio_static: ResMut<RadioStatic>,
+    mut instances: ResMut<Assets<AudioInstance>>,
+) {
+    let radio_static = radio_static.as_mut();
+    if mixer.is_changed() {
+        for handle in [&radio_static.hiss, &radio_static.crackle] {
+            if let Some(instance) = instances.get_mut(handle) {
+                if mixer.track(Track::Static).paused {
+                    instance.pause(AudioTween::default());
+                } else {
+                    instance.resume(AudioTween::default());
+                }
+            }
+        }
+    }
+    if !radio_static.refresh.tick(time.delta()).just_finished() {
+        return;
+    }
+    let Ok(freq) = radio.get_single() else {
+        return;
+    };
+
+    radio_static.burst = if radio_static.rng.gen_bool(BURST_CHANCE) {
+        radio_static.rng.gen_range(1.5..MAX_BURST)
+    } else {
+        1.0 + (radio_static.burst - 1.0) * BURST_DECAY
+    };
+    let levels = levels(freq.0, radio_static.signal);
+    let gain = mixer.gain(Track::Static);
+    let tween = AudioTween::linear(REFRESH);
+    if let Some(instance) = instances.get_mut(&radio_static.hiss) {
+        instance.set_volume(
+            levels.hiss * fade(time.elapsed_seconds_f64()) * gain,
+            tween.clone(),
+        );
+        instance.set_playback_rate(levels.playback_rate, tween.clone());
+    }
+    if let Some(instance) = instances.get_mut(&radio_static.crackle) {
+        instance.set_volume(levels.crackle * radio_static.burst * gain, tween);
+    }
+
+    let panning = if player.has(Flag::RightSpeakerBroken) {
+        0.0
+    } else {
+        0.5
+    };
+    if radio_static.panning != Some(panning) {
+        radio_static.panning = Some(panning);
+        for handle in [&radio_static.hiss, &radio_static.crackle] {
+            if let Some(instance) = instances.get_mut(handle) {
+                instance.set_panning(panning, AudioTween::default());
+            }
+        }
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    // After the solutions, which take the first roll.
+    app.add_systems(Startup, setup.after(solutions::setup));
+    app.add_systems(Update, update.in_set(ApplyMix));
+}
@@ -0,0 +1,236 @@
+use std::hash::Hash;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use bevy::app::AppExit;
+use bevy::asset::ron;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use serde::{Deserialize, Serialize};
+
+use crate::components::{GameRng, Keyboard, UpdateSet};
+use crate::input::{self, MousePosition};
+
+const SAVE_KEY: KeyCode = KeyCode::F10;
+
+/// Everything the game reads from input devices during a single frame.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InputFrame {
+    pub frame: u32,
+    pub delta: Duration,
+    pub cursor: Vec2,
+    pub mouse_buttons: Vec<MouseButton>,
+    pub keys: Vec<KeyCode>,
+    pub gamepad_buttons: Vec<GamepadButton>,
+    #[serde(default)]
+    pub gamepad_axes: Vec<(GamepadAxis, f32)>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Recording {
+    pub seed: u64,
+    pub frames: Vec<InputFrame>,
+}
+
+#[derive(Resource)]
+pub enum ReplayMode {
+    Off,
+    Recording { path: PathBuf, recording: Recording },
+    Replaying { recording: Recording, next: usize },
+}
+
+impl ReplayMode {
+    /// `--record <path>` records this session, `--replay <path>` plays one back.
+    pub fn from_args(mut args: impl Iterator<Item = String>) -> Self {
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--record" => {
+                    let Some(path) = args.next() else {
+                        break;
+                    };
+                    return Self::Recording {
+                        path: path.into(),
+                        recording: Recording {
+                            seed: rand::random(),
+                            frames: vec![],
+                        },
+                    };
+                }
+                "--replay" => {
+                    let Some(path) = args.next() else {
+                        break;
+                    };
+                    match Self::load(&path) {
+                        Ok(recording) => return Self::Replaying { recording, next: 0 },
+                        Err(err) => error!("failed to load recording {}: {}", path, err),
+                    }
+                }
+                _ => (),
+            }
+        }
+        Self::Off
+    }
+
+    fn load(path: &str) -> Result<Recording, Box<dyn std::error::Error>> {
+        let text = std::fs::read_to_string(path)?;
+        Ok(ron::from_str(&text)?)
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        match self {
+            Self::Off => None,
+            Self::Recording { recording, .. } | Self::Replaying { recording, .. } => {
+                Some(recording.seed)
+            }
+        }
+    }
+}
+
+fn pressed<T: Copy + Eq + Hash + Send + Sync + 'static>(input: &ButtonInput<T>) -> Vec<T> {
+    input.get_pressed().copied().collect()
+}
+
+fn axes(input: &Axis<GamepadAxis>) -> Vec<(GamepadAxis, f32)> {
+    input
+        .devices()
+        .filter_map(|&axis| Some((axis, input.get_unclamped(axis)?)))
+        .collect()
+}
+
+// Rebuilds the input state from scratch so that real devices can't leak into
+// a replay. Buttons held on the previous frame must not count as just pressed.
+fn apply_pressed<T: Copy + Eq + Hash + Send + Sync + 'static>(
+    input: &mut ButtonInput<T>,
+    pressed: &[T],
+    previously_pressed: &[T],
+) {
+    input.reset_all();
+    for button in pressed.iter().copied() {
+        input.press(button);
+        if previously_pressed.contains(&button) {
+            input.clear_just_pressed(button);
+        }
+    }
+}
+
+fn record(
+    mut mode: ResMut<ReplayMode>,
+    time: Res<Time>,
+    cursor: Res<MousePosition>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    keys: Keyboard,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+) {
+    let ReplayMode::Recording { recording, .. } = mode.as_mut() else {
+        return;
+    };
+
+    let frame = recording.frames.len() as u32;
+    recording.frames.push(InputFrame {
+        frame,
+        delta: time.delta(),
+        cursor: cursor.0,
+        mouse_buttons: pressed(&mouse_buttons),
+        keys: pressed(&keys),
+        gamepad_buttons: pressed(&gamepad_buttons),
+        gamepad_axes: axes(&gamepad_axes),
+    });
+}
+
+fn replay(
+    mut mode: ResMut<ReplayMode>,
+    mut cursor: ResMut<MousePosition>,
+    mut mouse_buttons: ResMut<ButtonInput<MouseButton>>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut gamepad_buttons: ResMut<ButtonInput<GamepadButton>>,
+    mut gamepad_axes: ResMut<Axis<GamepadAxis>>,
+) {
+    let ReplayMode::Replaying { recording, next } = mode.as_mut() else {
+        return;
+    };
+
+    let Some(frame) = recording.frames.get(*next) else {
+        info!("replay finished after {} frames", next);
+        *mode = ReplayMode::Off;
+        return;
+    };
+    let previous = next.checked_sub(1).map(|i| &recording.frames[i]);
+
+    cursor.0 = frame.cursor;
+    apply_pressed(
+        &mut mouse_buttons,
+        &frame.mouse_buttons,
+        previous.map_or(&[], |p| &p.mouse_buttons),
+    );
+    apply_pressed(&mut keys, &frame.keys, previous.map_or(&[], |p| &p.keys));
+    apply_pressed(
+        &mut gamepad_buttons,
+        &frame.gamepad_buttons,
+        previous.map_or(&[], |p| &p.gamepad_buttons),
+    );
+    let stale: Vec<GamepadAxis> = gamepad_axes.devices().copied().collect();
+    for axis in stale {
+        gamepad_axes.remove(axis);
+    }
+    for &(axis, value) in &frame.gamepad_axes {
+        gamepad_axes.set(axis, value);
+    }
+    *next += 1;
+}
+
+// Time has already advanced by the time Update runs, so the recorded delta of
+// the upcoming frame is queued here for the next `First` schedule.
+fn queue_replay_delta(mode: Res<ReplayMode>, mut strategy: ResMut<TimeUpdateStrategy>) {
+    let ReplayMode::Replaying { recording, next } = mode.as_ref() else {
+        return;
+    };
+
+    *strategy = match recording.frames.get(*next) {
+        Some(frame) => TimeUpdateStrategy::ManualDuration(frame.delta),
+        None => TimeUpdateStrategy::Automatic,
+    };
+}
+
+fn save_recording(mode: Res<ReplayMode>, keyboard: Keyboard, mut app_exit: EventReader<AppExit>) {
+    let ReplayMode::Recording { path, recording } = mode.as_ref() else {
+        return;
+    };
+    if !keyboard.just_pressed(SAVE_KEY) && app_exit.read().next().is_none() {
+        return;
+    }
+
+    let result = ron::to_string(recording)
+        .map_err(|err| err.to_string())
+        .and_then(|text| std::fs::write(path, text).map_err(|err| err.to_string()));
+    match result {
+        Ok(()) => info!(
+            "saved {} recorded frames to {}",
+            recording.frames.len(),
+            path.display()
+        ),
+        Err(err) => error!("failed to save recording: {}", err),
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    let mode = ReplayMode::from_args(std::env::args().skip(1));
+    match mode.seed() {
+        Some(seed) => app.insert_resource(GameRng::seeded(seed)),
+        None => app.init_resource::<GameRng>(),
+    };
+    if let ReplayMode::Replaying { recording, .. } = &mode {
+        if let Some(frame) = recording.frames.first() {
+            app.insert_resource(TimeUpdateStrategy::ManualDuration(frame.delta));
+        }
+    }
+    app.insert_resource(mode);
+    app.add_systems(
+        Update,
+        (replay, record)
+            .chain()
+            .after(input::input_update)
+            .in_set(UpdateSet::Input),
+    );
+    app.add_systems(Last, (queue_replay_delta, save_recording));
+}
@@ -1,8 +1,13 @@
 use std::time::Duration;
 
 use bevy::prelude::*;
+use rand::Rng;
 
-use crate::{gamedata::SceneId, player::SceneItem};
+use crate::{
+    components::{GameRng, UpdateSet},
+    gamedata::SceneId,
+    player::SceneItem,
+};
 
 #[derive(Component)]
 struct Smoke {
@@ -30,13 +35,14 @@ fn spawn_smoke(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut smoke_spawner: Query<&mut SmokeSpawner>,
+    mut rng: ResMut<GameRng>,
     time: Res<Time>,
 ) {
     for mut smoke_spawner in smoke_spawner.iter_mut() {
         smoke_spawner.timer.tick(time.delta());
         if smoke_spawner.timer.just_finished() {
-            let x = (rand::random::<f32>() - 0.5) * 0.6;
-            let y = rand::random::<f32>();
+            let x = (rng.0.gen::<f32>() - 0.5) * 0.6;
+            let y = rng.0.gen::<f32>();
 
             commands.spawn((
                 SpriteBundle {
@@ -73,6 +79,5 @@ fn update_smoke(
 
 pub fn plugin(app: &mut App) {
     app.add_event::<RightSpeakerDestroyed>();
-    app.add_systems(Update, spawn_smoke);
-    app.add_systems(Update, update_smoke);
+    app.add_systems(Update, (spawn_smoke, update_smoke).in_set(UpdateSet::Scene));
 }
@@ -1,10 +1,15 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
 
 use crate::{
-    components::{ClickableArea, ClickableShape, Rectangle},
-    gamedata::SceneId,
+    combination_lock::{CombinationLock, LockSolved, LockSounds, WheelLayout},
+    components::{ClickableArea, ClickableShape, Rectangle, UpdateSet},
+    gamedata::{LetterPuzzle, SceneId, PUZZLE_CHARACTERS},
     input::MousePosition,
-    player::{LoadScene, Player, ResetUniverse, SceneItem, SceneState},
+    player::{LoadScene, Player, ResetUniverse, SceneItem},
+    progress::{Action, Flag},
+    solutions::Solutions,
 };
 
 const PUZZLE_BUTTON_X_POSITIONS: [f32; 6] = [-105.0, -66.0, -24.0, 20.0, 59.0, 100.0];
@@ -13,124 +18,176 @@ const PUZZLE_BUTTON_Y_POSITION: f32 = -209.0;
 const PUZZLE_BUTTON_WIDTH: f32 = 45.0;
 const PUZZLE_BUTTON_HEIGHT: f32 = 45.0;
 
-const NUM_PUZZLE_CHARACTERS: usize = 12;
-const PUZZLE_CHARACTERS: [&'static str; NUM_PUZZLE_CHARACTERS] =
-    ["A", "B", "D", "E", "H", "I", "N", "O", "S", "R", "P", "U"];
+// How long the switch stays down before anything happens.
+const SWITCH_PRESS_TIME: Duration = Duration::from_millis(600);
 
-#[derive(Component)]
-struct PuzzleSegment {
-    word_pos: usize,     // position within solution word
-    sequence_pos: usize, // position within list of selectable characters
+/// The big switch on the wall. It restarts the universe until the panel
+/// below it is solved, then saves it instead.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum UniverseSwitch {
+    Restart,
+    Save,
+}
+
+impl UniverseSwitch {
+    const fn texture(self, pressed: bool) -> &'static str {
+        use UniverseSwitch::*;
+        match (self, pressed) {
+            (Restart, false) => "images/scenes/restart_universe_button.png",
+            (Restart, true) => "images/scenes/restart_universe_button_flipped.png",
+            (Save, false) => "images/scenes/save_universe_button.png",
+            (Save, true) => "images/scenes/save_universe_button_flipped.png",
+        }
+    }
 }
 
 #[derive(Component)]
-struct RestartUniverseButton;
+struct SwitchPressed(Timer);
+
+fn spawn_switch(commands: &mut Commands, asset_server: &AssetServer, switch: UniverseSwitch) {
+    commands.spawn((
+        SpriteBundle {
+            texture: asset_server.load(switch.texture(false)),
+            transform: Transform::from_xyz(0.0, 0.0, 1.0),
+            ..default()
+        },
+        ClickableShape::Rectangle(Rectangle::from_pos_width_height(
+            Vec2::new(-537.0, -37.0),
+            300.0,
+            600.0,
+        )),
+        switch,
+        SceneItem(SceneId::Behind),
+    ));
+}
 
 fn load_scene(
     mut commands: Commands,
     mut load_scene: EventReader<LoadScene>,
     player: Res<Player>,
+    solutions: Res<Solutions>,
     asset_server: Res<AssetServer>,
 ) {
     for load_scene in load_scene.read() {
         if load_scene.0 == SceneId::Behind {
+            // Shareable, so a run can be replayed with `--seed <code>`.
             commands.spawn((
-                ClickableShape::Rectangle(Rectangle::from_pos_width_height(
-                    Vec2::new(-537.0, -37.0),
-                    300.0,
-                    600.0,
-                )),
-                RestartUniverseButton,
+                Text2dBundle {
+                    text: Text::from_section(
+                        format!("universe {} #{}", solutions.seed, solutions.universe),
+                        TextStyle {
+                            font: asset_server.load("fonts/FiraMono-Regular.ttf"),
+                            font_size: 30.0,
+                            color: Color::srgba(1.0, 1.0, 1.0, 0.5),
+                        },
+                    ),
+                    transform: Transform::from_xyz(0.0, 500.0, 5.0),
+                    ..default()
+                },
                 SceneItem(SceneId::Behind),
             ));
+            let switch = if player.has(Flag::SaveSwitchUnlocked) {
+                UniverseSwitch::Save
+            } else {
+                UniverseSwitch::Restart
+            };
+            spawn_switch(&mut commands, &asset_server, switch);
 
             let style = TextStyle {
                 font: asset_server.load("fonts/FiraMono-Regular.ttf"),
                 font_size: 60.0,
                 color: Color::linear_rgb(0.2, 0.9, 0.4),
-                ..default()
             };
-
-            for (index, x) in PUZZLE_BUTTON_X_POSITIONS.iter().enumerate() {
-                let y = PUZZLE_BUTTON_Y_POSITION;
-
-                let puzzle_segment = player.behind_puzzle_state[index];
-                let puzzle_segment_char = PUZZLE_CHARACTERS[puzzle_segment];
-                let rect = Rectangle::from_pos_width_height(
-                    Vec2::new(*x, y),
-                    PUZZLE_BUTTON_WIDTH,
-                    PUZZLE_BUTTON_HEIGHT,
-                );
-
-                commands.spawn((
-                    PuzzleSegment {
-                        word_pos: index,
-                        sequence_pos: puzzle_segment,
-                    },
-                    Text2dBundle {
-                        text: Text::from_sections([TextSection::new(
-                            puzzle_segment_char,
-                            style.clone(),
-                        )])
-                        .with_justify(JustifyText::Center),
-                        transform: Transform::from_translation(Vec3::new(*x, y, 5.0)),
-                        ..default()
-                    },
-                    ClickableShape::Rectangle(rect),
-                    SceneItem(SceneId::Behind),
-                ));
+            let size = Vec2::new(PUZZLE_BUTTON_WIDTH, PUZZLE_BUTTON_HEIGHT);
+            let layout: Vec<WheelLayout> = PUZZLE_BUTTON_X_POSITIONS
+                .iter()
+                .map(|x| {
+                    WheelLayout::click_to_advance(
+                        Vec3::new(*x, PUZZLE_BUTTON_Y_POSITION, 5.0),
+                        size,
+                    )
+                })
+                .collect();
+
+            CombinationLock {
+                puzzle: LetterPuzzle::Behind,
+                alphabet: &PUZZLE_CHARACTERS,
+                solution: solutions.behind_code,
+                lockout: None,
+                sounds: LockSounds::Mechanical,
             }
+            .spawn(&mut commands, &player, SceneId::Behind, &layout, style);
         }
     }
 }
 
 fn update_puzzle(
+    mut commands: Commands,
     mut player: ResMut<Player>,
-    mouse_pos: Res<MousePosition>,
-    mouse_button: Res<ButtonInput<MouseButton>>,
-    mut puzzle_segments: Query<(&mut PuzzleSegment, &mut Text, &ClickableShape)>,
+    asset_server: Res<AssetServer>,
+    switches: Query<Entity, With<UniverseSwitch>>,
+    mut lock_solved: EventReader<LockSolved>,
 ) {
-    if !mouse_button.just_pressed(MouseButton::Left) {
-        return;
-    }
-
-    for mut puzzle_segment in puzzle_segments.iter_mut() {
-        if puzzle_segment.2.contains(mouse_pos.0) {
-            let mut sequence = puzzle_segment.0.sequence_pos;
-            sequence += 1;
-            if sequence == NUM_PUZZLE_CHARACTERS {
-                sequence = 0;
+    for LockSolved(puzzle) in lock_solved.read() {
+        if *puzzle == LetterPuzzle::Behind && player.flags.perform(Action::UnlockSaveSwitch) {
+            for switch in switches.iter() {
+                commands.entity(switch).despawn();
             }
-            puzzle_segment.0.sequence_pos = sequence;
-
-            puzzle_segment.1.sections[0].value = String::from(PUZZLE_CHARACTERS[sequence]);
-
-            player.behind_puzzle_state[puzzle_segment.0.word_pos] = sequence;
+            spawn_switch(&mut commands, &asset_server, UniverseSwitch::Save);
         }
     }
 }
 
-fn update_universe_button(
-    mut player: ResMut<Player>,
+fn update_switch(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
     mouse_pos: Res<MousePosition>,
     mouse_button: Res<ButtonInput<MouseButton>>,
-    button: Query<&ClickableShape, With<RestartUniverseButton>>,
-    mut reset_universe: EventWriter<ResetUniverse>
+    mut switches: Query<
+        (Entity, &UniverseSwitch, &ClickableShape, &mut Handle<Image>),
+        Without<SwitchPressed>,
+    >,
 ) {
     if !mouse_button.just_pressed(MouseButton::Left) {
         return;
     }
 
-    if let Ok(button) = button.get_single() {
-        if button.contains(mouse_pos.0) {
-            // TODO: Wire up other reset logic here
-            *player = Player::new();
-            player.scene = SceneState::ForceTransition(SceneId::Behind, SceneId::Desk);
-            reset_universe.send(ResetUniverse);
+    for (entity, switch, shape, mut texture) in switches.iter_mut() {
+        if shape.contains(mouse_pos.0) {
+            *texture = asset_server.load(switch.texture(true));
+            commands.entity(entity).insert(SwitchPressed(Timer::new(
+                SWITCH_PRESS_TIME,
+                TimerMode::Once,
+            )));
+        }
+    }
+}
+
+fn finish_switch(
+    mut player: ResMut<Player>,
+    time: Res<Time>,
+    mut switches: Query<(&UniverseSwitch, &mut SwitchPressed)>,
+    mut reset_universe: EventWriter<ResetUniverse>,
+) {
+    for (switch, mut pressed) in switches.iter_mut() {
+        if !pressed.0.tick(time.delta()).just_finished() {
+            continue;
+        }
+        match switch {
+            UniverseSwitch::Restart => {
+                player.restart();
+                reset_universe.send(ResetUniverse);
+            }
+            UniverseSwitch::Save => {
+                player.flags.perform(Action::PullSaveSwitch);
+            }
         }
     }
 }
 
 pub fn plugin(app: &mut App) {
-    app.add_systems(Update, (load_scene, update_puzzle, update_universe_button));
+    app.add_systems(
+        Update,
+        (load_scene, update_puzzle, update_switch, finish_switch).in_set(UpdateSet::Scene),
+    );
 }
@@ -1,10 +1,13 @@
 use bevy::prelude::*;
 
 use crate::{
-    components::{ClickableArea, ClickableLabel, ClickableShape, Rectangle},
-    gamedata::SceneId,
+    components::{ClickableArea, ClickableLabel, ClickableShape, Rectangle, UpdateSet},
+    event_log::GameplayEvent,
+    gamedata::{Item, SceneId},
     input::MousePosition,
     player::{LoadScene, Player, SceneItem},
+    progress::{Action, Flag},
+    solutions::Solutions,
 };
 
 #[derive(Component)]
@@ -17,11 +20,35 @@ fn load_scene(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     player: Res<Player>,
+    solutions: Res<Solutions>,
     mut load_scene: EventReader<LoadScene>,
 ) {
     for load_scene in load_scene.read() {
         if load_scene.0 == SceneId::BulletinBoard {
-            if !player.has_key {
+            // Which of the broadcast's digits to dial, and in what order.
+            let order: Vec<String> = solutions
+                .dial_order
+                .iter()
+                .map(|i| (i + 1).to_string())
+                .collect();
+            commands.spawn((
+                Text2dBundle {
+                    text: Text::from_section(
+                        format!("CALL IN ORDER\n{}", order.join(" ")),
+                        TextStyle {
+                            font: asset_server.load("fonts/FiraMono-Regular.ttf"),
+                            font_size: 50.0,
+                            color: Color::srgb(0.85, 0.85, 0.8),
+                        },
+                    )
+                    .with_justify(JustifyText::Center),
+                    transform: Transform::from_xyz(100.0, 150.0, 3.0),
+                    ..default()
+                },
+                SceneItem(SceneId::BulletinBoard),
+            ));
+
+            if !player.has(Flag::HasKey) {
                 commands.spawn((
                     SpriteBundle {
                         texture: asset_server.load("images/scenes/key.png"),
@@ -39,7 +66,7 @@ fn load_scene(
                 ));
             }
 
-            if player.has_surge_protector && !player.has_installed_surge_protector {
+            if player.has(Flag::HasSurgeProtector) && !player.has(Flag::InstalledSurgeProtector) {
                 commands.spawn((
                     ClickableShape::Rectangle(Rectangle::from_pos_width_height(
                         Vec2::new(255.0, -287.0),
@@ -52,7 +79,7 @@ fn load_scene(
                 ));
             }
 
-            if player.has_installed_surge_protector {
+            if player.has(Flag::InstalledSurgeProtector) {
                 commands.spawn((
                     SpriteBundle {
                         texture: asset_server.load("images/scenes/protected_outlet.png"),
@@ -71,15 +98,16 @@ fn update_key_clickable(
     mouse_pos: Res<MousePosition>,
     mouse_button: Res<ButtonInput<MouseButton>>,
     key: Query<(Entity, &ClickableShape), With<Key>>,
+    mut gameplay_events: EventWriter<GameplayEvent>,
 ) {
     if !mouse_button.just_pressed(MouseButton::Left) {
         return;
     }
 
     for key in key.iter() {
-        if key.1.contains(mouse_pos.0) {
-            player.has_key = true;
+        if key.1.contains(mouse_pos.0) && player.flags.perform(Action::TakeKey) {
             commands.entity(key.0).despawn();
+            gameplay_events.send(GameplayEvent::ItemPickedUp(Item::Key));
         }
     }
 }
@@ -91,15 +119,16 @@ fn update_outlet_clickable(
     mouse_button: Res<ButtonInput<MouseButton>>,
     asset_server: Res<AssetServer>,
     key: Query<(Entity, &ClickableShape), With<Outlet>>,
+    mut gameplay_events: EventWriter<GameplayEvent>,
 ) {
     if !mouse_button.just_pressed(MouseButton::Left) {
         return;
     }
 
     for key in key.iter() {
-        if key.1.contains(mouse_pos.0) {
-            player.has_installed_surge_protector = true;
+        if key.1.contains(mouse_pos.0) && player.flags.perform(Action::InstallSurgeProtector) {
             commands.entity(key.0).despawn();
+            gameplay_events.send(GameplayEvent::ItemUsed(Item::SurgeProtector));
             commands.spawn((
                 SpriteBundle {
                     texture: asset_server.load("images/scenes/protected_outlet.png"),
@@ -114,6 +143,6 @@ fn update_outlet_clickable(
 pub fn plugin(app: &mut App) {
     app.add_systems(
         Update,
-        (load_scene, update_key_clickable, update_outlet_clickable),
+        (load_scene, update_key_clickable, update_outlet_clickable).in_set(UpdateSet::Scene),
     );
 }
@@ -8,14 +8,23 @@ use bevy::{
 };
 
 use crate::{
-    components::{self, ClickableLabel, ClickableScene, ClickableShape},
+    components::{self, ClickableLabel, ClickableScene, ClickableShape, UpdateSet},
     gamedata::SceneId,
+    phone::PhoneLine,
     player::{LoadScene, Player, SceneItem},
+    progress::Flag,
     right_speaker::SmokeSpawner,
     tv::TvScreenMaterial,
 };
 
-use super::keypad_drawer::is_keypad_drawer_solved;
+const PHONE_LIGHT_POS: Vec3 = Vec3::new(-500.0, -110.0, 5.0);
+const PHONE_LIGHT_SIZE: Vec2 = Vec2::new(14.0, 14.0);
+// Blinks fast while ringing and slowly while there are missed calls.
+const RINGING_BLINK: f32 = 0.25;
+const MISSED_BLINK: f32 = 1.0;
+
+#[derive(Component)]
+struct PhoneLight;
 
 fn skewed_rectangle_builder(rect: Rectangle) -> Mesh {
     let [hw, hh] = [rect.half_size.x, rect.half_size.y];
@@ -111,6 +120,17 @@ fn load_scene(
             },
             SceneItem(SceneId::Desk),
         ));
+        // The whole desk in the lamp's light, under the TV screen.
+        if let Some(color) = player.lamp_light() {
+            commands.spawn((
+                SpriteBundle {
+                    texture: asset_server.load(format!("images/scenes/desk_{}.png", color.name())),
+                    transform: Transform::from_xyz(0.0, 0.0, 1.0),
+                    ..Default::default()
+                },
+                SceneItem(SceneId::Desk),
+            ));
+        }
         if let Ok(TvScreenMaterial(tv_screen)) = tv_screen.get_single() {
             let mesh = skewed_rectangle_builder(Rectangle::new(250.0, 242.0));
 
@@ -125,15 +145,30 @@ fn load_scene(
             ));
         }
 
-        if player.right_speaker_broken {
+        if player.has(Flag::RightSpeakerBroken) {
             commands.spawn((SmokeSpawner::new(), SceneItem(SceneId::Desk)));
         }
 
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::linear_rgb(1.0, 0.1, 0.05),
+                    custom_size: Some(PHONE_LIGHT_SIZE),
+                    ..default()
+                },
+                transform: Transform::from_translation(PHONE_LIGHT_POS),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            PhoneLight,
+            SceneItem(SceneId::Desk),
+        ));
+
         for dc in DESK_CLICKABLES.iter() {
             let to_scene = match dc.scene {
                 SceneId::LockDrawer => {
-                    if player.opened_key_drawer {
-                        if player.has_morse_code_translator {
+                    if player.has(Flag::OpenedKeyDrawer) {
+                        if player.has(Flag::HasMorseCodeTranslator) {
                             SceneId::LockDrawerEmpty
                         } else {
                             SceneId::LockDrawerSolved
@@ -143,8 +178,8 @@ fn load_scene(
                     }
                 }
                 SceneId::KeypadDrawer => {
-                    if is_keypad_drawer_solved(&player) {
-                        if player.has_surge_protector {
+                    if player.has(Flag::OpenedKeypadDrawer) {
+                        if player.has(Flag::HasSurgeProtector) {
                             SceneId::KeypadDrawerEmpty
                         } else {
                             SceneId::KeypadDrawerSolved
@@ -171,6 +206,33 @@ fn load_scene(
     }
 }
 
+fn blink_phone_light(
+    time: Res<Time>,
+    phone: Res<PhoneLine>,
+    mut lights: Query<&mut Visibility, With<PhoneLight>>,
+) {
+    let period = if phone.caller_id().is_some() {
+        RINGING_BLINK
+    } else {
+        MISSED_BLINK
+    };
+    let on = ((time.elapsed_seconds() / period) as u32).is_multiple_of(2);
+    let lit = phone.needs_attention() && on;
+    for mut visibility in lights.iter_mut() {
+        let wanted = if lit {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if *visibility != wanted {
+            *visibility = wanted;
+        }
+    }
+}
+
 pub fn plugin(app: &mut App) {
-    app.add_systems(Update, load_scene);
+    app.add_systems(
+        Update,
+        (load_scene, blink_phone_light).in_set(UpdateSet::Scene),
+    );
 }
@@ -1,15 +1,386 @@
-use bevy::prelude::*;
+//! The front door: a lock with three digit wheels, someone on the other side
+//! knocking its code out once the radio is on the air, a peephole to look
+//! through and, with the door unlocked and a light to see by, the way out.
 
-use crate::{gamedata::SceneId, player::LoadScene};
+use std::collections::VecDeque;
+use std::f32::consts::TAU;
+use std::sync::Arc;
+use std::time::Duration;
 
-fn load_scene(mut commands: Commands, mut load_scene: EventReader<LoadScene>) {
+use bevy::{
+    prelude::*,
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+};
+use bevy_kira_audio::prelude::*;
+
+use crate::{
+    camera::{HORIZONTAL_RESOLUTION, VERTICAL_RESOLUTION},
+    combination_lock::{CombinationLock, LockSolved, LockSounds, Lockout, WheelLayout},
+    components::{
+        self, ClickableArea, ClickableLabel, ClickableScene, ClickableShape, Rectangle, UpdateSet,
+    },
+    event_log::GameplayEvent,
+    gamedata::{LetterPuzzle, SceneId, DOOR_DIGITS},
+    input::MousePosition,
+    mixer::SfxChannel,
+    player::{LoadScene, Player, ResetUniverse, SceneItem},
+    progress::{Action, Flag},
+    solutions::Solutions,
+};
+
+// Above the handle, on the edge of the door.
+const LOCK_WHEEL_X_POSITIONS: [f32; 3] = [440.0, 500.0, 560.0];
+const LOCK_WHEEL_Y_POSITION: f32 = -230.0;
+const HANDLE: Vec2 = Vec2::new(650.0, -340.0);
+// At eye level in the middle of the door.
+const PEEPHOLE: Vec2 = Vec2::new(330.0, 140.0);
+const PEEPHOLE_RADIUS: f32 = 14.0;
+/// How much of the hallway shows through the peephole.
+const VIEW_RADIUS: f32 = 380.0;
+
+/// Silence between one round of knocking and the next.
+const KNOCK_INTERVAL: Duration = Duration::from_secs(40);
+const KNOCK_GAP: Duration = Duration::from_millis(300);
+/// From the last knock of one digit to the first of the next.
+const DIGIT_GAP: Duration = Duration::from_millis(1200);
+// With only three digits to a wheel, trying every combination has to cost
+// more than listening.
+const LOCKOUT: Lockout = Lockout {
+    attempts: 5,
+    duration: Duration::from_secs(10),
+};
+
+const SAMPLE_RATE: u32 = 44_100;
+const KNOCK_LENGTH: Duration = Duration::from_millis(150);
+const KNOCK_AMPLITUDE: f32 = 0.3;
+
+#[derive(Resource)]
+struct KnockSound(Handle<AudioSource>);
+
+/// Someone on the other side of the door, knocking out its code over and
+/// over until it's opened.
+#[derive(Resource)]
+struct Knocking {
+    until_next: Timer,
+    /// When each knock still to come in this round is due.
+    round: VecDeque<Duration>,
+    elapsed: Duration,
+}
+
+impl Default for Knocking {
+    fn default() -> Self {
+        Self {
+            until_next: Timer::new(KNOCK_INTERVAL, TimerMode::Repeating),
+            round: VecDeque::new(),
+            elapsed: Duration::ZERO,
+        }
+    }
+}
+
+#[derive(Component)]
+struct DoorHandle;
+
+/// Whoever is knocking, as seen through the peephole.
+#[derive(Component)]
+struct Visitor;
+
+#[derive(Component)]
+struct TooDark;
+
+/// A knuckle on wood: a few low partials that die away fast.
+fn knock_sound() -> AudioSource {
+    let length = (KNOCK_LENGTH.as_secs_f32() * SAMPLE_RATE as f32) as usize;
+    let frames: Arc<[Frame]> = (0..length)
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            let sample: f32 = [(95.0, 1.0), (160.0, 0.6), (340.0, 0.3)]
+                .iter()
+                .map(|(frequency, level)| level * (TAU * frequency * t).sin())
+                .sum();
+            Frame::from_mono(sample * (-40.0 * t).exp() * KNOCK_AMPLITUDE)
+        })
+        .collect();
+
+    AudioSource {
+        sound: StaticSoundData {
+            sample_rate: SAMPLE_RATE,
+            frames,
+            settings: StaticSoundSettings::default(),
+        },
+    }
+}
+
+/// Each digit of `code` as that many knocks.
+fn knock_times(code: &str) -> VecDeque<Duration> {
+    let mut times = VecDeque::new();
+    let mut at = Duration::ZERO;
+    for digit in code.chars().filter_map(|c| c.to_digit(10)) {
+        for _ in 0..digit {
+            times.push_back(at);
+            at += KNOCK_GAP;
+        }
+        at += DIGIT_GAP - KNOCK_GAP;
+    }
+    times
+}
+
+fn door_lock(solutions: &Solutions) -> CombinationLock {
+    CombinationLock {
+        puzzle: LetterPuzzle::Door,
+        alphabet: &DOOR_DIGITS,
+        solution: solutions.door_code,
+        lockout: Some(LOCKOUT),
+        sounds: LockSounds::Mechanical,
+    }
+}
+
+fn setup(mut commands: Commands, mut sources: ResMut<Assets<AudioSource>>) {
+    commands.insert_resource(KnockSound(sources.add(knock_sound())));
+}
+
+fn load_scene(
+    mut commands: Commands,
+    mut load_scene: EventReader<LoadScene>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    player: Res<Player>,
+    solutions: Res<Solutions>,
+    asset_server: Res<AssetServer>,
+) {
     for load_scene in load_scene.read() {
-        if load_scene.0 == SceneId::Door {}
+        match load_scene.0 {
+            SceneId::Door => {
+                commands.spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::srgb(0.12, 0.12, 0.11),
+                            custom_size: Some(Vec2::new(210.0, 100.0)),
+                            ..default()
+                        },
+                        transform: Transform::from_xyz(500.0, LOCK_WHEEL_Y_POSITION, 1.0),
+                        ..default()
+                    },
+                    SceneItem(SceneId::Door),
+                ));
+                let layout: Vec<WheelLayout> = LOCK_WHEEL_X_POSITIONS
+                    .iter()
+                    .map(|x| {
+                        WheelLayout::click_to_advance(
+                            Vec3::new(*x, LOCK_WHEEL_Y_POSITION, 2.0),
+                            Vec2::new(60.0, 90.0),
+                        )
+                    })
+                    .collect();
+                door_lock(&solutions).spawn(
+                    &mut commands,
+                    &player,
+                    SceneId::Door,
+                    &layout,
+                    TextStyle {
+                        font: asset_server.load("fonts/FiraMono-Regular.ttf"),
+                        font_size: 60.0,
+                        color: Color::srgb(0.8, 0.8, 0.75),
+                    },
+                );
+
+                commands.spawn((
+                    MaterialMesh2dBundle {
+                        mesh: Mesh2dHandle(meshes.add(Circle::new(PEEPHOLE_RADIUS))),
+                        material: materials.add(Color::srgb(0.05, 0.05, 0.05)),
+                        transform: Transform::from_translation(PEEPHOLE.extend(1.0)),
+                        ..default()
+                    },
+                    ClickableShape::Circle(components::Circle {
+                        center: PEEPHOLE,
+                        radius: PEEPHOLE_RADIUS * 3.0,
+                    }),
+                    ClickableLabel("Peephole"),
+                    ClickableScene {
+                        from: SceneId::Door,
+                        to: SceneId::Peephole,
+                    },
+                    SceneItem(SceneId::Door),
+                ));
+                commands.spawn((
+                    ClickableShape::Rectangle(Rectangle::from_pos_width_height(HANDLE, 90.0, 90.0)),
+                    ClickableLabel("Handle"),
+                    DoorHandle,
+                    SceneItem(SceneId::Door),
+                ));
+            }
+            SceneId::Peephole => {
+                commands.spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::BLACK,
+                            custom_size: Some(Vec2::new(
+                                HORIZONTAL_RESOLUTION,
+                                VERTICAL_RESOLUTION,
+                            )),
+                            ..default()
+                        },
+                        transform: Transform::from_xyz(0.0, 0.0, 1.0),
+                        ..default()
+                    },
+                    SceneItem(SceneId::Peephole),
+                ));
+                commands.spawn((
+                    MaterialMesh2dBundle {
+                        mesh: Mesh2dHandle(meshes.add(Circle::new(VIEW_RADIUS))),
+                        material: materials.add(Color::srgb(0.13, 0.13, 0.15)),
+                        transform: Transform::from_xyz(0.0, 0.0, 2.0),
+                        ..default()
+                    },
+                    SceneItem(SceneId::Peephole),
+                ));
+
+                let silhouette = materials.add(Color::srgb(0.03, 0.03, 0.03));
+                for (mesh, position) in [
+                    (meshes.add(Circle::new(70.0)), Vec3::new(0.0, 100.0, 3.0)),
+                    (
+                        meshes.add(Ellipse::new(130.0, 190.0)),
+                        Vec3::new(0.0, -160.0, 3.0),
+                    ),
+                ] {
+                    commands.spawn((
+                        MaterialMesh2dBundle {
+                            mesh: Mesh2dHandle(mesh),
+                            material: silhouette.clone(),
+                            transform: Transform::from_translation(position),
+                            visibility: Visibility::Hidden,
+                            ..default()
+                        },
+                        Visitor,
+                        SceneItem(SceneId::Peephole),
+                    ));
+                }
+            }
+            _ => (),
+        }
     }
 }
 
-fn update() {}
+fn unlock(mut player: ResMut<Player>, mut lock_solved: EventReader<LockSolved>) {
+    for LockSolved(puzzle) in lock_solved.read() {
+        if *puzzle == LetterPuzzle::Door {
+            player.flags.perform(Action::UnlockDoor);
+        }
+    }
+}
+
+fn open_door(
+    mut commands: Commands,
+    mut player: ResMut<Player>,
+    mouse_pos: Res<MousePosition>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    asset_server: Res<AssetServer>,
+    handles: Query<&ClickableShape, With<DoorHandle>>,
+    too_dark: Query<(), With<TooDark>>,
+) {
+    if !mouse_button.just_pressed(MouseButton::Left) {
+        return;
+    }
+    if !handles.iter().any(|shape| shape.contains(mouse_pos.0)) {
+        return;
+    }
+
+    // Going out is the end of the run, which the endings screen takes over.
+    if player.flags.perform(Action::OpenDoor) {
+        return;
+    }
+    if player.has(Flag::DoorUnlocked) && too_dark.is_empty() {
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    "TOO DARK OUT THERE\nTO GO WITHOUT A LIGHT.",
+                    TextStyle {
+                        font: asset_server.load("fonts/FiraMono-Regular.ttf"),
+                        font_size: 60.0,
+                        color: Color::srgb(0.8, 0.8, 0.75),
+                    },
+                )
+                .with_justify(JustifyText::Center),
+                transform: Transform::from_xyz(0.0, 300.0, 5.0),
+                ..default()
+            },
+            TooDark,
+            SceneItem(SceneId::Door),
+        ));
+    }
+}
+
+fn knock(
+    time: Res<Time>,
+    audio: Res<AudioChannel<SfxChannel>>,
+    sound: Res<KnockSound>,
+    mut player: ResMut<Player>,
+    solutions: Res<Solutions>,
+    mut knocking: ResMut<Knocking>,
+    mut reset_universe: EventReader<ResetUniverse>,
+    mut gameplay_events: EventWriter<GameplayEvent>,
+) {
+    let knocking = knocking.as_mut();
+    if reset_universe.read().count() > 0 {
+        *knocking = Knocking::default();
+    }
+
+    if knocking.round.is_empty() {
+        if !player.has(Flag::InstalledMorseCodeTranslator) || player.has(Flag::DoorUnlocked) {
+            return;
+        }
+        if !knocking.until_next.tick(time.delta()).just_finished() {
+            return;
+        }
+        knocking.round = knock_times(solutions.door_code);
+        knocking.elapsed = Duration::ZERO;
+        gameplay_events.send(GameplayEvent::DoorKnocked);
+    }
+
+    knocking.elapsed += time.delta();
+    while knocking
+        .round
+        .front()
+        .is_some_and(|at| *at <= knocking.elapsed)
+    {
+        knocking.round.pop_front();
+        audio.play(sound.0.clone());
+        if knocking.round.is_empty() {
+            heard_knocks(&mut player, &solutions);
+        }
+    }
+}
+
+// A lock already set to the code gives as soon as the bolt comes off.
+fn heard_knocks(player: &mut Player, solutions: &Solutions) {
+    if player.has(Flag::HeardKnocks) {
+        return;
+    }
+    player.flags.perform(Action::ListenToKnocks);
+    if door_lock(solutions).spells(player.letter_wheels(LetterPuzzle::Door)) {
+        player.flags.perform(Action::UnlockDoor);
+    }
+}
+
+fn update_visitor(knocking: Res<Knocking>, mut visitor: Query<&mut Visibility, With<Visitor>>) {
+    let visibility = if knocking.round.is_empty() {
+        Visibility::Hidden
+    } else {
+        Visibility::Inherited
+    };
+    for mut visible in visitor.iter_mut() {
+        if *visible != visibility {
+            *visible = visibility;
+        }
+    }
+}
 
 pub fn plugin(app: &mut App) {
-    app.add_systems(Update, (load_scene, update));
+    app.init_resource::<Knocking>();
+    app.add_systems(Startup, setup);
+    app.add_systems(
+        Update,
+        (load_scene, unlock, open_door, knock, update_visitor)
+            .chain()
+            .in_set(UpdateSet::Scene),
+    );
 }
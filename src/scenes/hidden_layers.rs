@@ -0,0 +1,76 @@
+//! Writing and marks around the room that only show up in the light of one
+//! particular bulb.
+
+use bevy::prelude::*;
+
+use crate::{
+    components::UpdateSet,
+    gamedata::SceneId,
+    player::{LightbulbColor, LoadScene, Player, SceneItem},
+};
+
+struct HiddenLayer {
+    scene: SceneId,
+    light: LightbulbColor,
+    text: &'static str,
+    position: Vec3,
+    font_size: f32,
+    color: Color,
+}
+
+const HIDDEN_LAYERS: [HiddenLayer; 2] = [
+    // Scrawled over the dial order, to point at the numbers station.
+    HiddenLayer {
+        scene: SceneId::BulletinBoard,
+        light: LightbulbColor::Red,
+        text: "THE STATION\nKNOWS THE NUMBER",
+        position: Vec3::new(-250.0, -150.0, 3.0),
+        font_size: 45.0,
+        color: Color::srgb(0.9, 0.15, 0.1),
+    },
+    // Marked under the desk, for dialing in green light.
+    HiddenLayer {
+        scene: SceneId::Desk,
+        light: LightbulbColor::Green,
+        text: "<< 9 8 7 6 5 4 3 2 1",
+        position: Vec3::new(0.0, -310.0, 3.0),
+        font_size: 35.0,
+        color: Color::srgb(0.45, 0.95, 0.35),
+    },
+];
+
+fn load_scene(
+    mut commands: Commands,
+    player: Res<Player>,
+    asset_server: Res<AssetServer>,
+    mut load_scene: EventReader<LoadScene>,
+) {
+    for LoadScene(scene) in load_scene.read() {
+        let light = player.lamp_light();
+        for layer in HIDDEN_LAYERS
+            .iter()
+            .filter(|layer| layer.scene == *scene && Some(layer.light) == light)
+        {
+            commands.spawn((
+                Text2dBundle {
+                    text: Text::from_section(
+                        layer.text,
+                        TextStyle {
+                            font: asset_server.load("fonts/FiraMono-Regular.ttf"),
+                            font_size: layer.font_size,
+                            color: layer.color,
+                        },
+                    )
+                    .with_justify(JustifyText::Center),
+                    transform: Transform::from_translation(layer.position),
+                    ..default()
+                },
+                SceneItem(*scene),
+            ));
+        }
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(Update, load_scene.in_set(UpdateSet::Scene));
+}
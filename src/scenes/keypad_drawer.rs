@@ -1,10 +1,12 @@
 use bevy::prelude::*;
 
 use crate::{
-    components::{ClickableArea, ClickableShape, Rectangle},
-    gamedata::SceneId,
-    input::MousePosition,
-    player::{LoadScene, Player, SceneItem, SceneState},
+    combination_lock::{CombinationLock, LockSolved, LockSounds, WheelLayout},
+    components::UpdateSet,
+    gamedata::{LetterPuzzle, SceneId, PUZZLE_CHARACTERS},
+    player::{LoadScene, Player, SceneState},
+    progress::Action,
+    solutions::Solutions,
 };
 
 const PUZZLE_BUTTON_X_POSITIONS: [f32; 3] = [215.0, 294.0, 376.0];
@@ -13,26 +15,11 @@ const PUZZLE_BUTTON_Y_POSITION: f32 = 177.0;
 const PUZZLE_BUTTON_WIDTH: f32 = 100.0;
 const PUZZLE_BUTTON_HEIGHT: f32 = 100.0;
 
-const NUM_PUZZLE_CHARACTERS: usize = 12;
-const PUZZLE_CHARACTERS: [&'static str; NUM_PUZZLE_CHARACTERS] =
-    ["A", "B", "D", "E", "H", "I", "N", "O", "S", "R", "P", "U"];
-
-#[derive(Component)]
-struct UpButton(usize);
-
-#[derive(Component)]
-struct DownButton(usize);
-
-#[derive(Component)]
-struct PuzzleSegment {
-    word_pos: usize,     // position within solution word
-    sequence_pos: usize, // position within list of selectable characters
-}
-
 fn load_scene(
     mut commands: Commands,
     mut load_scene: EventReader<LoadScene>,
     player: Res<Player>,
+    solutions: Res<Solutions>,
     asset_server: Res<AssetServer>,
 ) {
     for load_scene in load_scene.read() {
@@ -41,121 +28,50 @@ fn load_scene(
                 font: asset_server.load("fonts/FiraMono-Regular.ttf"),
                 font_size: 100.0,
                 color: Color::BLACK,
-                ..default()
             };
-
-            for (index, x) in PUZZLE_BUTTON_X_POSITIONS.iter().enumerate() {
-                let y = PUZZLE_BUTTON_Y_POSITION;
-
-                let puzzle_segment = player.keypad_drawer_puzzle_state[index];
-                let puzzle_segment_char = PUZZLE_CHARACTERS[puzzle_segment];
-                let up_rect = Rectangle::from_pos_width_height(
-                    Vec2::new(*x + 45.0, y + 180.0 + (5 * index) as f32),
-                    PUZZLE_BUTTON_WIDTH,
-                    PUZZLE_BUTTON_HEIGHT,
-                );
-                let down_rect = Rectangle::from_pos_width_height(
-                    Vec2::new(*x + 30.0, y - 224.0),
-                    PUZZLE_BUTTON_WIDTH,
-                    PUZZLE_BUTTON_HEIGHT,
-                );
-
-                commands.spawn((
-                    PuzzleSegment {
-                        word_pos: index,
-                        sequence_pos: puzzle_segment,
-                    },
-                    Text2dBundle {
-                        text: Text::from_sections([TextSection::new(
-                            puzzle_segment_char,
-                            style.clone(),
-                        )])
-                        .with_justify(JustifyText::Center),
-                        transform: Transform::from_translation(Vec3::new(
-                            *x,
-                            y + (5 * index) as f32,
-                            5.0,
-                        )),
-                        ..default()
-                    },
-                    SceneItem(SceneId::KeypadDrawer),
-                ));
-                commands.spawn((
-                    ClickableShape::Rectangle(up_rect),
-                    UpButton(index),
-                    SceneItem(SceneId::KeypadDrawer),
-                ));
-                commands.spawn((
-                    ClickableShape::Rectangle(down_rect),
-                    DownButton(index),
-                    SceneItem(SceneId::KeypadDrawer),
-                ));
+            let size = Vec2::new(PUZZLE_BUTTON_WIDTH, PUZZLE_BUTTON_HEIGHT);
+            let layout: Vec<WheelLayout> = PUZZLE_BUTTON_X_POSITIONS
+                .iter()
+                .enumerate()
+                .map(|(index, x)| {
+                    let y = PUZZLE_BUTTON_Y_POSITION;
+                    let skew = (5 * index) as f32;
+                    WheelLayout::up_down(
+                        Vec3::new(*x, y + skew, 5.0),
+                        Vec2::new(*x + 45.0, y + 180.0 + skew),
+                        Vec2::new(*x + 30.0, y - 224.0),
+                        size,
+                    )
+                })
+                .collect();
+
+            CombinationLock {
+                puzzle: LetterPuzzle::KeypadDrawer,
+                alphabet: &PUZZLE_CHARACTERS,
+                solution: solutions.keypad_code,
+                lockout: None,
+                sounds: LockSounds::Electronic,
             }
+            .spawn(
+                &mut commands,
+                &player,
+                SceneId::KeypadDrawer,
+                &layout,
+                style,
+            );
         }
     }
 }
 
-pub fn is_keypad_drawer_solved(player: &Player) -> bool {
-    PUZZLE_CHARACTERS[player.keypad_drawer_puzzle_state[0]] == "S"
-        && PUZZLE_CHARACTERS[player.keypad_drawer_puzzle_state[1]] == "E"
-        && PUZZLE_CHARACTERS[player.keypad_drawer_puzzle_state[2]] == "E"
-}
-
-fn update(
-    mut player: ResMut<Player>,
-    mouse_pos: Res<MousePosition>,
-    mouse_button: Res<ButtonInput<MouseButton>>,
-    up_buttons: Query<(&ClickableShape, &UpButton)>,
-    down_buttons: Query<(&ClickableShape, &DownButton)>,
-    mut puzzle_segments: Query<(&mut PuzzleSegment, &mut Text)>,
-) {
-    if !mouse_button.just_pressed(MouseButton::Left) {
-        return;
-    }
-
-    if is_keypad_drawer_solved(&player) {
-        return;
-    }
-
-    for mut puzzle_segment in puzzle_segments.iter_mut() {
-        for down_button in down_buttons.iter() {
-            if down_button.0.contains(mouse_pos.0) && down_button.1 .0 == puzzle_segment.0.word_pos
-            {
-                let mut sequence = puzzle_segment.0.sequence_pos;
-                if sequence == 0 {
-                    sequence = NUM_PUZZLE_CHARACTERS - 1;
-                } else {
-                    sequence -= 1;
-                }
-                puzzle_segment.0.sequence_pos = sequence;
-
-                puzzle_segment.1.sections[0].value = String::from(PUZZLE_CHARACTERS[sequence]);
-
-                player.keypad_drawer_puzzle_state[puzzle_segment.0.word_pos] = sequence;
-            }
-        }
-        for up_button in up_buttons.iter() {
-            if up_button.0.contains(mouse_pos.0) && up_button.1 .0 == puzzle_segment.0.word_pos {
-                let mut sequence = puzzle_segment.0.sequence_pos;
-                sequence += 1;
-                if sequence == NUM_PUZZLE_CHARACTERS {
-                    sequence = 0;
-                }
-                puzzle_segment.0.sequence_pos = sequence;
-
-                puzzle_segment.1.sections[0].value = String::from(PUZZLE_CHARACTERS[sequence]);
-
-                player.keypad_drawer_puzzle_state[puzzle_segment.0.word_pos] = sequence;
-            }
+fn update(mut player: ResMut<Player>, mut lock_solved: EventReader<LockSolved>) {
+    for LockSolved(puzzle) in lock_solved.read() {
+        if *puzzle == LetterPuzzle::KeypadDrawer && player.flags.perform(Action::OpenKeypadDrawer) {
+            player.scene =
+                SceneState::ForceTransition(SceneId::KeypadDrawer, SceneId::KeypadDrawerSolved);
         }
     }
-
-    if is_keypad_drawer_solved(&player) {
-        player.scene =
-            SceneState::ForceTransition(SceneId::KeypadDrawer, SceneId::KeypadDrawerSolved);
-    }
 }
 
 pub fn plugin(app: &mut App) {
-    app.add_systems(Update, (load_scene, update));
+    app.add_systems(Update, (load_scene, update).in_set(UpdateSet::Scene));
 }
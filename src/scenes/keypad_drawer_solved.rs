@@ -1,10 +1,12 @@
 use bevy::prelude::*;
 
 use crate::{
-    components::{ClickableArea, ClickableLabel, ClickableShape, Rectangle},
-    gamedata::SceneId,
+    components::{ClickableArea, ClickableLabel, ClickableShape, Rectangle, UpdateSet},
+    event_log::GameplayEvent,
+    gamedata::{Item, SceneId},
     input::MousePosition,
     player::{LoadScene, Player, SceneItem, SceneState},
+    progress::Action,
 };
 
 #[derive(Component)]
@@ -32,14 +34,15 @@ fn update(
     mouse_pos: Res<MousePosition>,
     mouse_button: Res<ButtonInput<MouseButton>>,
     clickables: Query<&ClickableShape, With<SurgeProtector>>,
+    mut gameplay_events: EventWriter<GameplayEvent>,
 ) {
     if !mouse_button.just_pressed(MouseButton::Left) {
         return;
     }
 
     for clickable in clickables.iter() {
-        if clickable.contains(mouse_pos.0) {
-            player.has_surge_protector = true;
+        if clickable.contains(mouse_pos.0) && player.flags.perform(Action::TakeSurgeProtector) {
+            gameplay_events.send(GameplayEvent::ItemPickedUp(Item::SurgeProtector));
             player.scene = SceneState::ForceTransition(
                 SceneId::KeypadDrawerSolved,
                 SceneId::KeypadDrawerEmpty,
@@ -49,5 +52,5 @@ fn update(
 }
 
 pub fn plugin(app: &mut App) {
-    app.add_systems(Update, (load_scene, update));
+    app.add_systems(Update, (load_scene, update).in_set(UpdateSet::Scene));
 }
@@ -1,35 +1,169 @@
 use bevy::prelude::*;
 
 use crate::{
-    gamedata::SceneId,
+    components::{ClickableArea, ClickableLabel, ClickableShape, Rectangle, UpdateSet},
+    event_log::GameplayEvent,
+    gamedata::{Item, SceneId},
+    input::MousePosition,
     player::{LightbulbColor, LoadScene, Player, SceneItem},
+    progress::Action,
 };
 
-fn load_scene(
-    mut commands: Commands,
+// Up under the shade.
+const SOCKET: Vec2 = Vec2::new(350.0, -80.0);
+const BULB: Vec3 = Vec3::new(340.0, -60.0, 2.0);
+// A pull chain hanging from the rim of the shade.
+const CHAIN: Vec3 = Vec3::new(140.0, -250.0, 4.0);
+const KNOB: Vec3 = Vec3::new(140.0, -345.0, 4.0);
+
+#[derive(Component)]
+struct Socket;
+
+#[derive(Component)]
+struct Switch;
+
+#[derive(Component)]
+struct Bulb;
+
+/// The lamp lit up, in the color of its bulb.
+#[derive(Component)]
+struct Glow;
+
+const fn bulb_color(color: LightbulbColor) -> Color {
+    match color {
+        LightbulbColor::Red => Color::srgb(0.6, 0.15, 0.1),
+        LightbulbColor::Green => Color::srgb(0.2, 0.55, 0.15),
+    }
+}
+
+fn load_scene(mut commands: Commands, mut load_scene: EventReader<LoadScene>) {
+    for load_scene in load_scene.read() {
+        if load_scene.0 != SceneId::Lamp {
+            continue;
+        }
+        commands.spawn((
+            ClickableShape::Rectangle(Rectangle::from_pos_width_height(SOCKET, 520.0, 200.0)),
+            ClickableLabel("Socket"),
+            Socket,
+            SceneItem(SceneId::Lamp),
+        ));
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::new(70.0, 90.0)),
+                    ..default()
+                },
+                transform: Transform::from_translation(BULB),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            Bulb,
+            SceneItem(SceneId::Lamp),
+        ));
+        commands.spawn((
+            SpriteBundle {
+                transform: Transform::from_xyz(0.0, 0.0, 3.0),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            Glow,
+            SceneItem(SceneId::Lamp),
+        ));
+
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgb(0.55, 0.55, 0.5),
+                    custom_size: Some(Vec2::new(6.0, 180.0)),
+                    ..default()
+                },
+                transform: Transform::from_translation(CHAIN),
+                ..default()
+            },
+            SceneItem(SceneId::Lamp),
+        ));
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgb(0.7, 0.7, 0.65),
+                    custom_size: Some(Vec2::new(30.0, 36.0)),
+                    ..default()
+                },
+                transform: Transform::from_translation(KNOB),
+                ..default()
+            },
+            ClickableShape::Rectangle(Rectangle::from_pos_width_height(
+                KNOB.truncate() + Vec2::new(0.0, 45.0),
+                90.0,
+                160.0,
+            )),
+            ClickableLabel("Switch"),
+            Switch,
+            SceneItem(SceneId::Lamp),
+        ));
+    }
+}
+
+fn update(
     mut player: ResMut<Player>,
-    asset_server: Res<AssetServer>,
-    mut load_scene: EventReader<LoadScene>,
+    mouse_pos: Res<MousePosition>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    socket: Query<&ClickableShape, With<Socket>>,
+    switch: Query<&ClickableShape, With<Switch>>,
+    mut gameplay_events: EventWriter<GameplayEvent>,
 ) {
-    for load_scene in load_scene.read() {
-        if load_scene.0 == SceneId::Lamp {
-            player.installed_lightbulb = player.lightbulb_unlock;
-            if let Some(LightbulbColor::Red) = player.installed_lightbulb {
-                commands.spawn((
-                    SpriteBundle {
-                        texture: asset_server.load("images/scenes/lamp_red.png"),
-                        transform: Transform::from_xyz(0.0, 0.0, 3.0),
-                        ..Default::default()
-                    },
-                    SceneItem(SceneId::Lamp),
-                ));
+    if !mouse_button.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    if socket.iter().any(|shape| shape.contains(mouse_pos.0)) {
+        // Whatever bulb is carried goes in, swapping out the other one.
+        if let Some(color) = player.lightbulb_unlock() {
+            if player.flags.perform(Action::InstallLightbulb(color)) {
+                gameplay_events.send(GameplayEvent::ItemUsed(Item::Lightbulb(color)));
             }
         }
     }
+    if switch.iter().any(|shape| shape.contains(mouse_pos.0)) {
+        player.flags.perform(Action::PullLampSwitch);
+    }
 }
 
-fn update() {}
+fn update_lamp(
+    player: Res<Player>,
+    asset_server: Res<AssetServer>,
+    mut bulb: Query<(&mut Sprite, &mut Visibility), (With<Bulb>, Without<Glow>)>,
+    mut glow: Query<(&mut Handle<Image>, &mut Visibility), With<Glow>>,
+) {
+    for (mut sprite, mut visibility) in bulb.iter_mut() {
+        match player.installed_lightbulb() {
+            Some(color) => {
+                sprite.color = bulb_color(color);
+                *visibility = Visibility::Inherited;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+    // Only the lamp scene has a glow, so this doesn't load anything elsewhere.
+    for (mut texture, mut visibility) in glow.iter_mut() {
+        match player.lamp_light() {
+            Some(color) => {
+                let lit = asset_server.load(format!("images/scenes/lamp_{}.png", color.name()));
+                if *texture != lit {
+                    *texture = lit;
+                }
+                *visibility = Visibility::Inherited;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+}
 
 pub fn plugin(app: &mut App) {
-    app.add_systems(Update, (load_scene, update));
+    app.add_systems(
+        Update,
+        (load_scene, update, update_lamp)
+            .chain()
+            .in_set(UpdateSet::Scene),
+    );
 }
@@ -1,10 +1,12 @@
 use bevy::prelude::*;
 
 use crate::{
-    components::{ClickableArea, ClickableLabel, ClickableShape, Rectangle},
-    gamedata::SceneId,
+    components::{ClickableArea, ClickableLabel, ClickableShape, Rectangle, UpdateSet},
+    event_log::GameplayEvent,
+    gamedata::{Item, SceneId},
     input::MousePosition,
     player::{LoadScene, Player, SceneItem, SceneState},
+    progress::Action,
 };
 
 #[derive(Component)]
@@ -32,6 +34,7 @@ fn update(
     mouse_pos: Res<MousePosition>,
     mouse_button: Res<ButtonInput<MouseButton>>,
     key: Query<(Entity, &ClickableShape), With<Key>>,
+    mut gameplay_events: EventWriter<GameplayEvent>,
 ) {
     if !mouse_button.just_pressed(MouseButton::Left) {
         return;
@@ -39,8 +42,8 @@ fn update(
 
     for key in key.iter() {
         if key.1.contains(mouse_pos.0) {
-            if player.has_key {
-                player.opened_key_drawer = true;
+            if player.flags.perform(Action::UnlockKeyDrawer) {
+                gameplay_events.send(GameplayEvent::ItemUsed(Item::Key));
                 player.scene =
                     SceneState::ForceTransition(SceneId::LockDrawer, SceneId::LockDrawerSolved);
             }
@@ -49,5 +52,5 @@ fn update(
 }
 
 pub fn plugin(app: &mut App) {
-    app.add_systems(Update, (load_scene, update));
+    app.add_systems(Update, (load_scene, update).in_set(UpdateSet::Scene));
 }
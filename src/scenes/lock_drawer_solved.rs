@@ -1,19 +1,67 @@
 use bevy::prelude::*;
 
-
 use crate::{
-    components::{ClickableArea, ClickableLabel, ClickableShape, Rectangle},
-    gamedata::SceneId,
+    components::{ClickableArea, ClickableLabel, ClickableShape, Rectangle, UpdateSet},
+    event_log::GameplayEvent,
+    gamedata::{Item, SceneId},
     input::MousePosition,
     player::{LoadScene, Player, SceneItem, SceneState},
+    progress::{Action, Flag},
 };
 
+// Lies at the back of the drawer, next to the radio module.
+const FLASHLIGHT: Vec2 = Vec2::new(450.0, -200.0);
+const FLASHLIGHT_ANGLE: f32 = -0.5;
+
 #[derive(Component)]
 struct MorseCodeTranslator;
 
-fn load_scene(mut commands: Commands, mut load_scene: EventReader<LoadScene>) {
+#[derive(Component)]
+struct Flashlight;
+
+fn load_scene(mut commands: Commands, player: Res<Player>, mut load_scene: EventReader<LoadScene>) {
     for load_scene in load_scene.read() {
-        if load_scene.0 == SceneId::LockDrawerSolved {
+        let scene = load_scene.0;
+        if !matches!(scene, SceneId::LockDrawerSolved | SceneId::LockDrawerEmpty) {
+            continue;
+        }
+        if !player.has(Flag::HasFlashlight) {
+            let rotation = Quat::from_rotation_z(FLASHLIGHT_ANGLE);
+            let head = FLASHLIGHT + Vec2::from_angle(FLASHLIGHT_ANGLE) * 100.0;
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::srgb(0.15, 0.15, 0.17),
+                        custom_size: Some(Vec2::new(170.0, 42.0)),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(FLASHLIGHT.extend(3.0))
+                        .with_rotation(rotation),
+                    ..default()
+                },
+                ClickableShape::Rectangle(Rectangle::from_pos_width_height(
+                    FLASHLIGHT, 240.0, 160.0,
+                )),
+                ClickableLabel("Flashlight"),
+                Flashlight,
+                SceneItem(scene),
+            ));
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::srgb(0.55, 0.55, 0.5),
+                        custom_size: Some(Vec2::new(40.0, 62.0)),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(head.extend(3.1))
+                        .with_rotation(rotation),
+                    ..default()
+                },
+                Flashlight,
+                SceneItem(scene),
+            ));
+        }
+        if scene == SceneId::LockDrawerSolved {
             commands.spawn((
                 ClickableShape::Rectangle(Rectangle::from_pos_width_height(
                     Vec2::new(-100.0, 100.0),
@@ -33,22 +81,36 @@ fn update(
     mouse_pos: Res<MousePosition>,
     mouse_button: Res<ButtonInput<MouseButton>>,
     clickables: Query<&ClickableShape, With<MorseCodeTranslator>>,
+    flashlight: Query<(Entity, Option<&ClickableShape>), With<Flashlight>>,
+    mut commands: Commands,
+    mut gameplay_events: EventWriter<GameplayEvent>,
 ) {
     if !mouse_button.just_pressed(MouseButton::Left) {
         return;
     }
 
+    let flashlight_clicked = flashlight
+        .iter()
+        .filter_map(|(_, clickable)| clickable)
+        .any(|clickable| clickable.contains(mouse_pos.0));
+    if flashlight_clicked && player.flags.perform(Action::TakeFlashlight) {
+        gameplay_events.send(GameplayEvent::ItemPickedUp(Item::Flashlight));
+        for (entity, _) in flashlight.iter() {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+
     for clickable in clickables.iter() {
-        if clickable.contains(mouse_pos.0) {
-            player.has_morse_code_translator = true;
-            player.scene = SceneState::ForceTransition(
-                SceneId::LockDrawerSolved,
-                SceneId::LockDrawerEmpty,
-            );
+        if clickable.contains(mouse_pos.0) && player.flags.perform(Action::TakeMorseCodeTranslator)
+        {
+            gameplay_events.send(GameplayEvent::ItemPickedUp(Item::RadioModule));
+            player.scene =
+                SceneState::ForceTransition(SceneId::LockDrawerSolved, SceneId::LockDrawerEmpty);
         }
     }
 }
 
 pub fn plugin(app: &mut App) {
-    app.add_systems(Update, (load_scene, update));
+    app.add_systems(Update, (load_scene, update).in_set(UpdateSet::Scene));
 }
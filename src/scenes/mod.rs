@@ -4,6 +4,7 @@ mod behind;
 mod bulletin_board;
 mod desk;
 mod door;
+mod hidden_layers;
 mod keypad_drawer;
 mod keypad_drawer_solved;
 mod lamp;
@@ -24,6 +25,8 @@ pub fn plugin(app: &mut App) {
         lock_drawer_solved::plugin,
         tv::plugin,
         desk::plugin,
+        door::plugin,
+        hidden_layers::plugin,
         radio::plugin,
         phone::plugin
     ));
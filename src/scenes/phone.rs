@@ -1,10 +1,11 @@
 use bevy::prelude::*;
 
 use crate::{
-    components::{ClickableArea, ClickableShape, Rectangle},
-    gamedata::SceneId,
-    input::MousePosition,
-    player::{LightbulbColor, LoadScene, Player, SceneItem},
+    components::{ClickableArea, ClickableLabel, ClickableShape, Keyboard, Rectangle, UpdateSet},
+    gamedata::{SceneId, PHONE_BUTTON_LABELS},
+    input::{typed_digit, MousePosition},
+    phone::{PhoneInput, PhoneLine},
+    player::{LoadScene, Player, SceneItem, UnloadScene},
 };
 
 const BUTTONS: [Vec2; 9] = [
@@ -19,9 +20,20 @@ const BUTTONS: [Vec2; 9] = [
     Vec2::new(327.0, -356.0),
 ];
 
+const HANDSET: Vec2 = Vec2::new(150.0, 355.0);
+const HANDSET_WIDTH: f32 = 1100.0;
+const HANDSET_HEIGHT: f32 = 260.0;
+const DISPLAY_POS: Vec3 = Vec3::new(-620.0, 420.0, 5.0);
+
 #[derive(Component)]
 pub struct Button(usize);
 
+#[derive(Component)]
+struct Handset;
+
+#[derive(Component)]
+struct Display;
+
 fn load_scene(
     mut commands: Commands,
     player: Res<Player>,
@@ -30,10 +42,39 @@ fn load_scene(
 ) {
     for load_scene in load_scene.read() {
         if load_scene.0 == SceneId::Phone {
-            if let Some(LightbulbColor::Red) = player.installed_lightbulb {
+            commands.spawn((
+                ClickableShape::Rectangle(Rectangle::from_pos_width_height(
+                    HANDSET,
+                    HANDSET_WIDTH,
+                    HANDSET_HEIGHT,
+                )),
+                ClickableLabel("Handset"),
+                Handset,
+                SceneItem(SceneId::Phone),
+            ));
+            commands.spawn((
+                Text2dBundle {
+                    text: Text::from_sections([
+                        TextSection::from_style(display_style(&asset_server)),
+                        TextSection::new("\n", display_style(&asset_server)),
+                        TextSection::from_style(display_style(&asset_server)),
+                    ])
+                    .with_justify(JustifyText::Left),
+                    text_anchor: bevy::sprite::Anchor::TopLeft,
+                    transform: Transform::from_translation(DISPLAY_POS),
+                    ..default()
+                },
+                Display,
+                SceneItem(SceneId::Phone),
+            ));
+
+            // The buttons can only be made out with the lamp on, in whichever
+            // colour it lights the phone.
+            if let Some(color) = player.lamp_light() {
                 commands.spawn((
                     SpriteBundle {
-                        texture: asset_server.load("images/scenes/phone_red.png"),
+                        texture: asset_server
+                            .load(format!("images/scenes/phone_{}.png", color.name())),
                         transform: Transform::from_xyz(0.0, 0.0, 3.0),
                         ..Default::default()
                     },
@@ -56,45 +97,91 @@ fn load_scene(
     }
 }
 
+fn display_style(asset_server: &AssetServer) -> TextStyle {
+    TextStyle {
+        font: asset_server.load("fonts/FiraMono-Regular.ttf"),
+        font_size: 40.0,
+        color: Color::linear_rgb(0.2, 0.9, 0.4),
+    }
+}
+
 fn update(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut player: ResMut<Player>,
+    line: Res<PhoneLine>,
     mouse_pos: Res<MousePosition>,
     mouse_button: Res<ButtonInput<MouseButton>>,
+    keyboard: Keyboard,
+    handset: Query<&ClickableShape, With<Handset>>,
     buttons: Query<(&ClickableShape, &Button)>,
+    mut phone_input: EventWriter<PhoneInput>,
 ) {
-    if !mouse_button.just_pressed(MouseButton::Left) {
-        return;
+    let clicked = mouse_button.just_pressed(MouseButton::Left);
+    if clicked && handset.iter().any(|shape| shape.contains(mouse_pos.0)) {
+        phone_input.send(if line.off_hook() {
+            PhoneInput::HangUp
+        } else {
+            PhoneInput::PickUp
+        });
     }
 
-    for button in buttons.iter() {
-        if button.0.contains(mouse_pos.0) {
-            player.dialed_numbers.push(button.1 .0);
-        }
+    let mut pressed: Vec<usize> = buttons
+        .iter()
+        .filter(|(shape, _)| clicked && shape.contains(mouse_pos.0))
+        .map(|(_, button)| button.0)
+        .collect();
+    // Typed digits press the button with that label, if the phone is usable.
+    if !buttons.is_empty() {
+        pressed.extend(
+            keyboard
+                .get_just_pressed()
+                .filter_map(|key| typed_digit(*key))
+                .filter_map(|digit| PHONE_BUTTON_LABELS.iter().position(|label| *label == digit)),
+        );
+    }
+    for button in pressed {
+        phone_input.send(PhoneInput::Press(button));
+    }
+}
 
-        if player.dialed_numbers.len() > 9 {
-            player.dialed_numbers.remove(0);
+fn update_display(
+    line: Res<PhoneLine>,
+    player: Res<Player>,
+    mut display: Query<&mut Text, With<Display>>,
+) {
+    for mut text in display.iter_mut() {
+        let number: String = match line.caller_id() {
+            Some(number) => number.iter().map(|digit| digit.to_string()).collect(),
+            None if line.off_hook() => player
+                .dialed_numbers
+                .iter()
+                .map(|button| PHONE_BUTTON_LABELS[*button].to_string())
+                .collect(),
+            None => String::new(),
+        };
+        if text.sections[0].value != number {
+            text.sections[0].value = number;
         }
+        let status = line.status();
+        if text.sections[2].value != status {
+            text.sections[2].value = status;
+        }
+    }
+}
 
-        let solution = vec![8, 5, 3, 1, 7, 6, 0, 2, 5];
-
-        let matching = player
-            .dialed_numbers
-            .iter()
-            .zip(&solution)
-            .filter(|&(a, b)| a == b)
-            .count();
-        if matching == 9 {
-            commands.spawn((SpriteBundle {
-                texture: asset_server.load("images/scenes/universe_saved.png"),
-                transform: Transform::from_xyz(0.0, 0.0, 20.0),
-                ..Default::default()
-            },));
+// The handset goes back on the hook when the player walks away.
+fn unload_scene(
+    mut unload_scene: EventReader<UnloadScene>,
+    mut phone_input: EventWriter<PhoneInput>,
+) {
+    for UnloadScene(scene) in unload_scene.read() {
+        if *scene == SceneId::Phone {
+            phone_input.send(PhoneInput::HangUp);
         }
     }
 }
 
 pub fn plugin(app: &mut App) {
-    app.add_systems(Update, (load_scene, update));
+    app.add_systems(
+        Update,
+        (load_scene, update, update_display, unload_scene).in_set(UpdateSet::Scene),
+    );
 }
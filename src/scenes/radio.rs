@@ -1,16 +1,72 @@
 use std::time::Duration;
 
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
 use bevy::prelude::*;
 
 use crate::{
-    audio::RadioAudio,
-    components::{self, ClickableArea, ClickableLabel, ClickableShape},
-    gamedata::{AmRadioFreq, PresetAmRadioFreq, SceneId},
+    audio::{RadioAudio, STATION_INNER_BANDWIDTH_DELTA},
+    components::{self, ClickableArea, ClickableLabel, ClickableShape, UpdateSet},
+    event_log::GameplayEvent,
+    gamedata::{AmRadioFreq, Item, PresetAmRadioFreq, SceneId},
     input::MousePosition,
-    player::{LoadScene, Player, SceneItem},
+    lighting::Flicker,
+    player::{LoadScene, Player, SceneItem, SceneState},
+    progress::{Action, Flag},
     right_speaker::RightSpeakerDestroyed,
+    solutions::Solutions,
 };
 
+const SPEAKER_FLICKER: Duration = Duration::from_millis(1500);
+
+// The frequency window doubles as the tuning scale, with the AM band spread
+// across it.
+const DIAL_LEFT: f32 = -110.0;
+const DIAL_RIGHT: f32 = 680.0;
+const DIAL_TOP: f32 = 170.0;
+const DIAL_BOTTOM: f32 = 80.0;
+const NEEDLE_WIDTH: f32 = 4.0;
+const NEEDLE_COLOR: Color = Color::srgb(0.7, 0.1, 0.1);
+// kHz per notch of the mouse wheel.
+const SCROLL_STEP: f32 = 5.0;
+// How far a touchpad scrolls for one notch of a wheel.
+const SCROLL_PIXELS_PER_NOTCH: f32 = 40.0;
+// Holding an arrow keeps tuning, after a moment.
+const FINE_TUNE_DELAY: Duration = Duration::from_millis(400);
+const FINE_TUNE_REPEAT: Duration = Duration::from_millis(50);
+
+#[derive(Component)]
+struct TuningDial;
+
+#[derive(Component)]
+struct TuningNeedle;
+
+/// One of the arrows, and the kHz it tunes by.
+#[derive(Component)]
+struct FineTuneButton(i32);
+
+const FINE_TUNE_BUTTONS: [(FineTuneButton, Vec2, Vec2); 2] = [
+    (
+        FineTuneButton(-1),
+        Vec2::new(-60.0, -110.0),
+        Vec2::new(60.0, -225.0),
+    ),
+    (
+        FineTuneButton(1),
+        Vec2::new(155.0, -110.0),
+        Vec2::new(275.0, -225.0),
+    ),
+];
+
+fn dial_position(freq: i32) -> f32 {
+    let t = (freq - AmRadioFreq::MIN) as f32 / (AmRadioFreq::MAX - AmRadioFreq::MIN) as f32;
+    DIAL_LEFT + (DIAL_RIGHT - DIAL_LEFT) * t
+}
+
+fn dial_freq(x: f32) -> i32 {
+    let t = ((x - DIAL_LEFT) / (DIAL_RIGHT - DIAL_LEFT)).clamp(0.0, 1.0);
+    AmRadioFreq::MIN + (t * (AmRadioFreq::MAX - AmRadioFreq::MIN) as f32).round() as i32
+}
+
 #[derive(Component)]
 struct MorseCodeTranslatorSlot;
 
@@ -90,11 +146,12 @@ fn load_scene(
     mut commands: Commands,
     player: Res<Player>,
     asset_server: Res<AssetServer>,
+    radio: Query<&AmRadioFreq, With<RadioAudio>>,
     mut load_scene: EventReader<LoadScene>,
 ) {
     for load_scene in load_scene.read() {
         if load_scene.0 == SceneId::Radio {
-            if player.has_installed_morse_code_translator {
+            if player.has(Flag::InstalledMorseCodeTranslator) {
                 commands.spawn((
                     make_translator(&asset_server),
                     MorseCodeTranslator {
@@ -103,7 +160,7 @@ fn load_scene(
                     SceneItem(SceneId::Radio),
                 ));
             } else {
-                let text = if player.has_installed_morse_code_translator {
+                let text = if player.has(Flag::HasMorseCodeTranslator) {
                     "Install Module"
                 } else {
                     "Module Slot"
@@ -135,6 +192,41 @@ fn load_scene(
                     SceneItem(SceneId::Radio),
                 ));
             }
+            let freq = radio.get_single().map_or(AmRadioFreq::MIN, |radio| radio.0);
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: NEEDLE_COLOR,
+                        custom_size: Some(Vec2::new(NEEDLE_WIDTH, DIAL_TOP - DIAL_BOTTOM)),
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(
+                        dial_position(freq),
+                        (DIAL_TOP + DIAL_BOTTOM) / 2.0,
+                        3.0,
+                    ),
+                    ..default()
+                },
+                ClickableShape::Rectangle(components::Rectangle {
+                    top_left: Vec2::new(DIAL_LEFT, DIAL_TOP),
+                    bottom_right: Vec2::new(DIAL_RIGHT, DIAL_BOTTOM),
+                }),
+                ClickableLabel("Tuning"),
+                TuningDial,
+                TuningNeedle,
+                SceneItem(SceneId::Radio),
+            ));
+            for (button, top_left, bottom_right) in FINE_TUNE_BUTTONS {
+                commands.spawn((
+                    ClickableShape::Rectangle(components::Rectangle {
+                        top_left,
+                        bottom_right,
+                    }),
+                    button,
+                    ClickableLabel("Fine Tune"),
+                    SceneItem(SceneId::Radio),
+                ));
+            }
         }
     }
 }
@@ -158,6 +250,72 @@ fn update(
     }
 }
 
+fn tune(
+    mouse_pos: Res<MousePosition>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    time: Res<Time>,
+    player: Res<Player>,
+    dials: Query<&ClickableShape, With<TuningDial>>,
+    fine_tune_buttons: Query<(&ClickableShape, &FineTuneButton)>,
+    mut mouse_wheel: EventReader<MouseWheel>,
+    mut dragging: Local<bool>,
+    mut held: Local<Option<(i32, Timer)>>,
+    mut radio: Query<&mut AmRadioFreq, (With<RadioAudio>, Without<ClickableShape>)>,
+) {
+    let Ok(mut radio) = radio.get_single_mut() else {
+        return;
+    };
+    let mut freq = radio.0;
+
+    if mouse_button.just_pressed(MouseButton::Left) {
+        *dragging = dials.iter().any(|dial| dial.contains(mouse_pos.0));
+        *held = fine_tune_buttons
+            .iter()
+            .find(|(shape, _)| shape.contains(mouse_pos.0))
+            .map(|(_, FineTuneButton(step))| {
+                freq += step;
+                (*step, Timer::new(FINE_TUNE_DELAY, TimerMode::Once))
+            });
+    }
+    // Leaving the scene lets go of the dial too.
+    if !mouse_button.pressed(MouseButton::Left) || dials.is_empty() {
+        *dragging = false;
+        *held = None;
+    }
+
+    if *dragging {
+        freq = dial_freq(mouse_pos.0.x);
+    }
+    if let Some((step, timer)) = held.as_mut() {
+        timer.tick(time.delta());
+        if timer.finished() {
+            freq += *step;
+            *timer = Timer::new(FINE_TUNE_REPEAT, TimerMode::Once);
+        }
+    }
+    let on_radio = matches!(player.scene, SceneState::Active(SceneId::Radio));
+    for wheel in mouse_wheel.read().filter(|_| on_radio) {
+        let notches = match wheel.unit {
+            MouseScrollUnit::Line => wheel.y,
+            MouseScrollUnit::Pixel => wheel.y / SCROLL_PIXELS_PER_NOTCH,
+        };
+        freq += (notches * SCROLL_STEP).round() as i32;
+    }
+
+    radio.set_if_neq(AmRadioFreq(freq.clamp(AmRadioFreq::MIN, AmRadioFreq::MAX)));
+}
+
+fn update_needle(
+    radio: Query<&AmRadioFreq, (With<RadioAudio>, Changed<AmRadioFreq>)>,
+    mut needles: Query<&mut Transform, With<TuningNeedle>>,
+) {
+    for radio in radio.iter() {
+        for mut needle in needles.iter_mut() {
+            needle.translation.x = dial_position(radio.0);
+        }
+    }
+}
+
 fn update_morse_code_translator_slot(
     mut commands: Commands,
     mut player: ResMut<Player>,
@@ -166,18 +324,23 @@ fn update_morse_code_translator_slot(
     asset_server: Res<AssetServer>,
     translator: Query<(Entity, &ClickableShape), With<MorseCodeTranslatorSlot>>,
     mut right_speaker_destroyed: EventWriter<RightSpeakerDestroyed>,
+    mut flicker: EventWriter<Flicker>,
+    mut gameplay_events: EventWriter<GameplayEvent>,
 ) {
     if !mouse_button.just_pressed(MouseButton::Left) {
         return;
     }
 
     for translator in translator.iter() {
-        if translator.1.contains(mouse_pos.0) {
-            player.has_installed_morse_code_translator = true;
+        if translator.1.contains(mouse_pos.0)
+            && player.flags.perform(Action::InstallMorseCodeTranslator)
+        {
             commands.entity(translator.0).despawn();
-            if !player.has_installed_surge_protector {
-                player.right_speaker_broken = true;
+            gameplay_events.send(GameplayEvent::ItemUsed(Item::RadioModule));
+            if player.has(Flag::RightSpeakerBroken) {
                 right_speaker_destroyed.send(RightSpeakerDestroyed);
+                // The blown speaker takes the lights with it for a moment.
+                flicker.send(Flicker(SPEAKER_FLICKER));
             }
             commands.spawn((
                 make_translator(&asset_server),
@@ -193,6 +356,7 @@ fn update_morse_code_translator_slot(
 fn update_morse_code_translator(
     mut translator: Query<(&mut MorseCodeTranslator, &mut Text)>,
     player: Res<Player>,
+    solutions: Res<Solutions>,
     time: Res<Time>,
     radio: Query<&AmRadioFreq, With<RadioAudio>>,
 ) {
@@ -201,12 +365,9 @@ fn update_morse_code_translator(
 
         if translator.0.timer.just_finished() {
             for radio in radio.iter() {
-                translator.1.sections[0].value = if radio.0 == PresetAmRadioFreq::Morse.value() {
-                    if player.right_speaker_broken {
-                        String::from("SEE")
-                    } else {
-                        String::from("BEHIND")
-                    }
+                let delta = (radio.0 - PresetAmRadioFreq::Morse.value()).abs();
+                translator.1.sections[0].value = if delta <= STATION_INNER_BANDWIDTH_DELTA {
+                    String::from(solutions.morse_word(&player))
                 } else {
                     String::from("???")
                 };
@@ -221,8 +382,11 @@ pub fn plugin(app: &mut App) {
         (
             load_scene,
             update,
+            tune,
+            update_needle,
             update_morse_code_translator_slot,
             update_morse_code_translator,
-        ),
+        )
+            .in_set(UpdateSet::Scene),
     );
 }
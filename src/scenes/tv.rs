@@ -8,7 +8,7 @@ use bevy::{
 };
 
 use crate::{
-    components::{self, ClickableLabel, ClickableScene, ClickableShape},
+    components::{self, ClickableLabel, ClickableScene, ClickableShape, UpdateSet},
     gamedata::SceneId,
     player::{LoadScene, SceneItem},
     tv::TvScreenMaterial,
@@ -77,5 +77,5 @@ fn load_scene(
 fn update() {}
 
 pub fn plugin(app: &mut App) {
-    app.add_systems(Update, (load_scene, update));
+    app.add_systems(Update, (load_scene, update).in_set(UpdateSet::Scene));
 }
@@ -0,0 +1,287 @@
+//! The player's options, kept between sessions and applied as soon as they
+//! change, including on startup.
+
+use bevy::asset::ron;
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowMode};
+use serde::{Deserialize, Serialize};
+
+const SETTINGS_KEY: &str = "settings.ron";
+
+/// Steps the volume sliders snap to.
+pub const VOLUME_STEP: f64 = 0.05;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Volume {
+    Radio,
+    Static,
+    Sfx,
+    Music,
+}
+
+pub const ALL_VOLUMES: [Volume; 4] = [Volume::Radio, Volume::Static, Volume::Sfx, Volume::Music];
+
+impl Volume {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Radio => "RADIO",
+            Self::Static => "STATIC",
+            Self::Sfx => "SFX",
+            Self::Music => "MUSIC",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TextSize {
+    Small,
+    #[default]
+    Normal,
+    Large,
+}
+
+impl TextSize {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Small => "SMALL",
+            Self::Normal => "NORMAL",
+            Self::Large => "LARGE",
+        }
+    }
+
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Small => Self::Normal,
+            Self::Normal => Self::Large,
+            Self::Large => Self::Small,
+        }
+    }
+
+    pub const fn scale(self) -> f32 {
+        match self {
+            Self::Small => 0.8,
+            Self::Normal => 1.0,
+            Self::Large => 1.25,
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+// Options added later fall back to their defaults in older files.
+#[serde(default)]
+pub struct Settings {
+    pub radio_volume: f64,
+    pub static_volume: f64,
+    pub sfx_volume: f64,
+    pub music_volume: f64,
+    pub radio_muted: bool,
+    pub static_muted: bool,
+    pub sfx_muted: bool,
+    pub music_muted: bool,
+    pub fullscreen: bool,
+    pub pixelation: bool,
+    pub text_size: TextSize,
+    /// No flashing lights or wobbling pixels.
+    pub reduced_motion: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            radio_volume: 1.0,
+            static_volume: 1.0,
+            sfx_volume: 1.0,
+            music_volume: 1.0,
+            radio_muted: false,
+            static_muted: false,
+            sfx_muted: false,
+            music_muted: false,
+            fullscreen: false,
+            pixelation: true,
+            text_size: TextSize::Normal,
+            reduced_motion: false,
+        }
+    }
+}
+
+impl Settings {
+    pub fn volume(&self, volume: Volume) -> f64 {
+        match volume {
+            Volume::Radio => self.radio_volume,
+            Volume::Static => self.static_volume,
+            Volume::Sfx => self.sfx_volume,
+            Volume::Music => self.music_volume,
+        }
+    }
+
+    /// Sets `volume` to `level`, snapped to a slider step.
+    pub fn set_volume(&mut self, volume: Volume, level: f64) {
+        let level = ((level / VOLUME_STEP).round() * VOLUME_STEP).clamp(0.0, 1.0);
+        *match volume {
+            Volume::Radio => &mut self.radio_volume,
+            Volume::Static => &mut self.static_volume,
+            Volume::Sfx => &mut self.sfx_volume,
+            Volume::Music => &mut self.music_volume,
+        } = level;
+    }
+
+    pub fn muted(&self, volume: Volume) -> bool {
+        match volume {
+            Volume::Radio => self.radio_muted,
+            Volume::Static => self.static_muted,
+            Volume::Sfx => self.sfx_muted,
+            Volume::Music => self.music_muted,
+        }
+    }
+
+    /// Mutes `volume`, or unmutes it at the level it had.
+    pub fn toggle_muted(&mut self, volume: Volume) {
+        let muted = match volume {
+            Volume::Radio => &mut self.radio_muted,
+            Volume::Static => &mut self.static_muted,
+            Volume::Sfx => &mut self.sfx_muted,
+            Volume::Music => &mut self.music_muted,
+        };
+        *muted = !*muted;
+    }
+}
+
+/// Where the settings are kept between sessions: a file on native builds and
+/// the browser's local storage on the web. Without a key they only last the
+/// session.
+#[derive(Resource, Debug, Default)]
+pub struct SettingsStore {
+    pub key: Option<String>,
+}
+
+impl SettingsStore {
+    pub fn load(&self) -> Result<Option<Settings>, Box<dyn std::error::Error>> {
+        let Some(key) = &self.key else {
+            return Ok(None);
+        };
+        match read(key)? {
+            Some(text) => Ok(Some(ron::from_str(&text)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn save(&self, settings: &Settings) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(key) = &self.key {
+            write(key, &ron::to_string(settings)?)?;
+        }
+        Ok(())
+    }
+}
+
+/// The text kept under `key`, if any.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read(key: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let path = std::path::Path::new(key);
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(std::fs::read_to_string(path)?))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write(key: &str, text: &str) -> Result<(), Box<dyn std::error::Error>> {
+    std::fs::write(key, text)?;
+    Ok(())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Result<web_sys::Storage, Box<dyn std::error::Error>> {
+    web_sys::window()
+        .and_then(|window| window.local_storage().ok().flatten())
+        .ok_or_else(|| "local storage is unavailable".into())
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn read(key: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    local_storage()?
+        .get_item(key)
+        .map_err(|err| format!("{:?}", err).into())
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn write(key: &str, text: &str) -> Result<(), Box<dyn std::error::Error>> {
+    local_storage()?
+        .set_item(key, text)
+        .map_err(|err| format!("{:?}", err).into())
+}
+
+/// The size a text was spawned with, before the text size setting scaled it.
+#[derive(Component)]
+struct BaseFontSizes(Vec<f32>);
+
+fn load_settings(store: Res<SettingsStore>, mut settings: ResMut<Settings>) {
+    match store.load() {
+        Ok(Some(loaded)) => *settings = loaded,
+        Ok(None) => (),
+        Err(err) => error!("failed to load settings: {}", err),
+    }
+}
+
+pub fn save_settings(store: Res<SettingsStore>, settings: Res<Settings>) {
+    if let Err(err) = store.save(&settings) {
+        error!("failed to save settings: {}", err);
+    }
+}
+
+fn apply_window_mode(
+    settings: Res<Settings>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    let mode = if settings.fullscreen {
+        WindowMode::BorderlessFullscreen
+    } else {
+        WindowMode::Windowed
+    };
+    for mut window in windows.iter_mut() {
+        if window.mode != mode {
+            window.mode = mode;
+        }
+    }
+}
+
+fn scale_text(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut new_texts: Query<(Entity, &mut Text), Without<BaseFontSizes>>,
+    mut texts: Query<(&BaseFontSizes, &mut Text)>,
+) {
+    let scale = settings.text_size.scale();
+    for (entity, mut text) in new_texts.iter_mut() {
+        let sizes = text
+            .sections
+            .iter()
+            .map(|section| section.style.font_size)
+            .collect();
+        commands.entity(entity).insert(BaseFontSizes(sizes));
+        if scale != 1.0 {
+            for section in text.sections.iter_mut() {
+                section.style.font_size *= scale;
+            }
+        }
+    }
+    if !settings.is_changed() {
+        return;
+    }
+    for (BaseFontSizes(sizes), mut text) in texts.iter_mut() {
+        for (section, size) in text.sections.iter_mut().zip(sizes) {
+            section.style.font_size = size * scale;
+        }
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.insert_resource(SettingsStore {
+        key: Some(SETTINGS_KEY.to_string()),
+    });
+    app.init_resource::<Settings>();
+    app.add_systems(Startup, load_settings);
+    app.add_systems(Update, (apply_window_mode, scale_text));
+}
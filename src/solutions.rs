@@ -0,0 +1,163 @@
+//! Puzzle answers, picked from a seed so that every run can be different and
+//! any run can be shared as a short code.
+//!
+//! The keypad code is learned in one universe and used in the next, so it is
+//! fixed for the whole run. So is the door code, as whoever knocks it out is
+//! on the other side of every universe. Everything else is learned and used within a
+//! single universe and is picked again on every reset.
+//!
+//! The numbers station is a recorded voice, so the digits it reads are the one
+//! thing that can't change. Only the order they're dialed in does, which the
+//! bulletin board gives away, so every phone number is a rearrangement of the
+//! same nine digits.
+
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::components::GameRng;
+use crate::player::{Player, ResetUniverse};
+use crate::progress::Flag;
+
+const KEYPAD_CODES: [&str; 20] = [
+    "SEE", "BED", "SUN", "RUN", "DOE", "PIE", "HUB", "ROB", "NOD", "PEN", "DIP", "HIS", "HER",
+    "ASH", "BUD", "DEN", "HEN", "PUN", "SOB", "URN",
+];
+/// Each digit is knocked out as that many knocks.
+const DOOR_CODES: [&str; 10] = [
+    "314", "241", "523", "152", "435", "213", "341", "524", "132", "453",
+];
+const BEHIND_CODES: [&str; 11] = [
+    "BEHIND", "SPIDER", "BRUISE", "PRISON", "POISED", "BANISH", "PUNISH", "SHRINE", "ORPHAN",
+    "BURDEN", "ABOUND",
+];
+
+/// What the numbers station reads out, in the order it reads it. Fixed by the
+/// recording in `audio/number-station.ogg`.
+pub const BROADCAST_DIGITS: [usize; 9] = [8, 5, 3, 1, 7, 6, 0, 2, 5];
+
+// Crockford's base 32, which leaves out letters that look like digits.
+const SEED_CODE_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const SEED_CODE_LEN: usize = 7;
+
+/// A run's seed, shown to the player as a code they can pass around.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeedCode(pub u32);
+
+impl SeedCode {
+    pub fn parse(code: &str) -> Option<Self> {
+        let code = code.trim();
+        if code.is_empty() || code.chars().count() > SEED_CODE_LEN {
+            return None;
+        }
+        let mut seed: u64 = 0;
+        for c in code.chars() {
+            let c = match c.to_ascii_uppercase() {
+                'O' => '0',
+                'I' | 'L' => '1',
+                c => c,
+            };
+            let digit = SEED_CODE_ALPHABET.iter().position(|a| *a as char == c)?;
+            seed = seed.checked_mul(32)?.checked_add(digit as u64)?;
+        }
+        u32::try_from(seed).ok().map(Self)
+    }
+}
+
+impl std::fmt::Display for SeedCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut digits = [0u8; SEED_CODE_LEN];
+        let mut seed = self.0;
+        for digit in digits.iter_mut().rev() {
+            *digit = SEED_CODE_ALPHABET[(seed % 32) as usize];
+            seed /= 32;
+        }
+        f.write_str(std::str::from_utf8(&digits).unwrap())
+    }
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct Solutions {
+    pub seed: SeedCode,
+    /// How many times the universe has been reset this run.
+    pub universe: u32,
+    pub keypad_code: &'static str,
+    pub door_code: &'static str,
+    pub behind_code: &'static str,
+    /// Positions in the broadcast, in the order they have to be dialed.
+    pub dial_order: [usize; 9],
+}
+
+impl Solutions {
+    pub fn generate(seed: SeedCode, universe: u32) -> Self {
+        let mut run_rng = StdRng::seed_from_u64(seed.0 as u64);
+        let mut universe_rng = StdRng::seed_from_u64((seed.0 as u64) << 32 | universe as u64);
+        let mut dial_order: [usize; 9] = std::array::from_fn(|i| i);
+        dial_order.shuffle(&mut universe_rng);
+        Self {
+            seed,
+            universe,
+            keypad_code: KEYPAD_CODES.choose(&mut run_rng).unwrap(),
+            door_code: DOOR_CODES.choose(&mut run_rng).unwrap(),
+            behind_code: BEHIND_CODES.choose(&mut universe_rng).unwrap(),
+            dial_order,
+        }
+    }
+
+    /// What the morse station sends. With the right speaker blown the
+    /// translator picks up the keypad code instead.
+    pub fn morse_word(&self, player: &Player) -> &'static str {
+        if player.has(Flag::RightSpeakerBroken) {
+            self.keypad_code
+        } else {
+            self.behind_code
+        }
+    }
+
+    pub fn phone_number(&self) -> [usize; 9] {
+        self.dial_order.map(|i| BROADCAST_DIGITS[i])
+    }
+}
+
+fn seed_from_args(mut args: impl Iterator<Item = String>) -> Option<SeedCode> {
+    while let Some(arg) = args.next() {
+        if arg == "--seed" {
+            let code = args.next()?;
+            let seed = SeedCode::parse(&code);
+            if seed.is_none() {
+                error!("invalid seed code {}", code);
+            }
+            return seed;
+        }
+    }
+    None
+}
+
+/// The solutions for a new run, from the `--seed` it was started with or else
+/// a fresh seed. The first reset of the run moves it into its first universe.
+pub fn new_run(rng: &mut GameRng) -> Solutions {
+    let seed = seed_from_args(std::env::args().skip(1)).unwrap_or_else(|| SeedCode(rng.0.gen()));
+    info!("universe seed {}", seed);
+    Solutions::generate(seed, 0)
+}
+
+// Runs at startup so that a replay's seeded `GameRng` also picks the same
+// solutions.
+pub fn setup(mut commands: Commands, mut rng: ResMut<GameRng>) {
+    commands.insert_resource(new_run(&mut rng));
+}
+
+fn on_reset_universe(
+    mut solutions: ResMut<Solutions>,
+    mut reset_universe: EventReader<ResetUniverse>,
+) {
+    for _ in reset_universe.read() {
+        *solutions = Solutions::generate(solutions.seed, solutions.universe + 1);
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(Startup, setup);
+    app.add_systems(Update, on_reset_universe);
+}
@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use super::menu::FIRST_ITEM;
+use super::Harness;
+use crate::endings::{Ending, EndingScreen, Gallery, ALL_ENDINGS};
+use crate::game_state::GameState;
+use crate::gamedata::SceneId;
+use crate::mixer::{Mixer, Track, ALL_TRACKS};
+use crate::player::Player;
+use crate::progress::{Action, Flags, ENDINGS};
+
+// Longer than any ending takes to show everything.
+const ENDING_TIME: Duration = Duration::from_secs(12);
+
+fn ending_screen(h: &Harness) -> &EndingScreen {
+    h.app.world().resource::<EndingScreen>()
+}
+
+fn escape(h: &mut Harness) {
+    let mut player = h.app.world_mut().resource_mut::<Player>();
+    for action in [
+        Action::TakeKey,
+        Action::UnlockKeyDrawer,
+        Action::TakeFlashlight,
+        Action::TakeMorseCodeTranslator,
+        Action::InstallMorseCodeTranslator,
+        Action::ListenToKnocks,
+        Action::UnlockDoor,
+        Action::OpenDoor,
+    ] {
+        assert!(player.flags.perform(action), "{:?}", action);
+    }
+}
+
+#[test]
+fn every_ending_has_a_screen() {
+    let flags: Vec<_> = ALL_ENDINGS.iter().map(|ending| ending.flag()).collect();
+    assert_eq!(flags, ENDINGS);
+    for ending in ALL_ENDINGS {
+        let mut reached = Flags::default();
+        reached.insert(ending.flag());
+        assert_eq!(Ending::reached(&reached), Some(ending));
+    }
+}
+
+#[test]
+fn ending_plays_then_returns_to_the_menu() {
+    let mut h = Harness::new();
+    let universe = h.solutions().universe;
+    escape(&mut h);
+    h.advance(2);
+    assert_eq!(h.state(), GameState::Ending);
+    assert_eq!(ending_screen(&h).ending(), Some(Ending::Escaped));
+    assert!(h.has_text("ESCAPED"));
+
+    // Nothing moves on until the whole ending has been shown.
+    h.click(Vec2::ZERO);
+    assert_eq!(ending_screen(&h).ending(), Some(Ending::Escaped));
+    h.advance_time(ENDING_TIME);
+    h.tap_key(KeyCode::Space);
+    assert_eq!(h.state(), GameState::MainMenu);
+    assert_eq!(ending_screen(&h).ending(), None);
+    assert!(!h.has_text("ESCAPED"));
+    // The run is over, so there's nothing to continue.
+    assert!(!h.has_text("CONTINUE"));
+
+    h.click(FIRST_ITEM);
+    assert_eq!(h.state(), GameState::Playing);
+    assert_eq!(h.player().flags, Flags::default());
+    assert_eq!(h.solutions().universe, universe);
+    h.wait_for_scene(SceneId::Desk);
+}
+
+#[test]
+fn ending_stops_the_room() {
+    let mut h = Harness::new();
+    escape(&mut h);
+    h.advance(2);
+    assert_eq!(h.state(), GameState::Ending);
+    assert!(h.app.world().resource::<Time<Virtual>>().is_paused());
+    let mixer = h.app.world().resource::<Mixer>();
+    for track in ALL_TRACKS {
+        assert_eq!(
+            mixer.track(track).paused,
+            track != Track::Music,
+            "{:?}",
+            track
+        );
+    }
+}
+
+#[test]
+fn gallery_remembers_each_ending_once() {
+    let mut h = Harness::new();
+    escape(&mut h);
+    h.advance_time(ENDING_TIME);
+    h.tap_key(KeyCode::Space);
+    h.click(FIRST_ITEM);
+    escape(&mut h);
+    h.advance(1);
+    assert!(h.has_text("ENDINGS FOUND 1/5\n???   ???   ???   ???   ESCAPED"));
+    assert_eq!(
+        h.app.world().resource::<Gallery>().reached,
+        [Ending::Escaped]
+    );
+}
+
+#[test]
+fn gallery_survives_a_restart() {
+    let path = std::env::temp_dir().join(format!("endings-{}.ron", std::process::id()));
+    let key = path.to_string_lossy().into_owned();
+    let mut gallery = Gallery {
+        key: Some(key.clone()),
+        reached: vec![],
+    };
+    assert!(gallery.record(Ending::Traded));
+    assert!(gallery.record(Ending::Saved));
+    assert!(!gallery.record(Ending::Traded));
+    gallery.save().unwrap();
+
+    let mut loaded = Gallery {
+        key: Some(key),
+        reached: vec![],
+    };
+    loaded.load().unwrap();
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(loaded.reached, [Ending::Traded, Ending::Saved]);
+}
@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+
+use super::Harness;
+use crate::gamedata::SceneId;
+use crate::hints::{Goal, TIER_DELAYS};
+use crate::progress::{Action, Flags};
+
+const HINT_KEY: KeyCode = KeyCode::KeyH;
+const BULLETIN_BOARD_KEY: Vec2 = Vec2::new(-585.0, 347.0);
+
+#[test]
+fn first_hint_points_at_unvisited_place() {
+    let mut h = Harness::new();
+    h.tap_key(HINT_KEY);
+    assert!(h.has_text("You haven't looked at the bulletin board yet."));
+}
+
+#[test]
+fn tiers_unlock_while_stuck() {
+    let mut h = Harness::new();
+    h.key_into(KeyCode::KeyA, SceneId::BulletinBoard);
+    let hints = Goal::FindKey.hints(h.solutions());
+
+    h.tap_key(HINT_KEY);
+    assert!(h.has_text(&hints[0]));
+    h.tap_key(HINT_KEY);
+    assert!(h.has_text(&hints[0]));
+
+    h.advance_time(TIER_DELAYS[1]);
+    h.tap_key(HINT_KEY);
+    assert!(h.has_text(&hints[1]));
+}
+
+#[test]
+fn progress_starts_over_at_a_nudge() {
+    let mut h = Harness::new();
+    h.advance_time(TIER_DELAYS[2]);
+    h.key_into(KeyCode::KeyA, SceneId::BulletinBoard);
+    h.click(BULLETIN_BOARD_KEY);
+
+    h.tap_key(HINT_KEY);
+    assert!(h.has_text("You haven't looked at the top drawer yet."));
+}
+
+#[test]
+fn broken_speaker_asks_for_a_reset() {
+    let mut flags = Flags::default();
+    for action in [
+        Action::TakeKey,
+        Action::UnlockKeyDrawer,
+        Action::TakeMorseCodeTranslator,
+        Action::InstallMorseCodeTranslator,
+    ] {
+        flags.perform(action);
+    }
+    assert_eq!(Goal::current(&flags), Some(Goal::OpenKeypadDrawer));
+
+    flags.perform(Action::OpenKeypadDrawer);
+    flags.perform(Action::TakeSurgeProtector);
+    assert_eq!(Goal::current(&flags), Some(Goal::ResetUniverse));
+}
@@ -0,0 +1,137 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use super::Harness;
+use crate::game_state::GameState;
+use crate::gamedata::SceneId;
+use crate::mixer::{Mixer, Track, ALL_TRACKS};
+use crate::phone::{LineState, PhoneLine};
+use crate::player::{Player, SceneState};
+use crate::progress::{Action, Flags};
+
+// Menu items, top to bottom.
+pub const FIRST_ITEM: Vec2 = Vec2::new(0.0, 60.0);
+pub const SECOND_ITEM: Vec2 = Vec2::new(0.0, -40.0);
+pub const THIRD_ITEM: Vec2 = Vec2::new(0.0, -140.0);
+
+// Longer than the door takes to start knocking.
+const KNOCK_WAIT: Duration = Duration::from_secs(41);
+// Longer than the first call takes to come in.
+const CALL_WAIT: Duration = Duration::from_secs(151);
+
+fn quit_to_menu(h: &mut Harness) {
+    h.tap_key(KeyCode::Escape);
+    h.click(THIRD_ITEM);
+    assert_eq!(h.state(), GameState::MainMenu);
+}
+
+#[test]
+fn escape_pauses_and_resumes() {
+    let mut h = Harness::new();
+    h.tap_key(KeyCode::Escape);
+    assert_eq!(h.state(), GameState::Paused);
+    assert!(h.has_text("PAUSED"));
+
+    // The room underneath doesn't take input while paused.
+    h.tap_key(KeyCode::KeyD);
+    h.advance(30);
+    assert!(matches!(h.scene(), SceneState::Active(SceneId::Desk)));
+
+    h.tap_key(KeyCode::Escape);
+    assert_eq!(h.state(), GameState::Playing);
+    assert!(!h.has_text("PAUSED"));
+    h.key_into(KeyCode::KeyD, SceneId::Door);
+}
+
+#[test]
+fn pause_quiets_every_track_but_the_music() {
+    let mut h = Harness::new();
+    let paused = |h: &Harness| {
+        let mixer = h.app.world().resource::<Mixer>();
+        ALL_TRACKS.map(|track| (track, mixer.track(track).paused))
+    };
+    assert!(paused(&h).iter().all(|(_, paused)| !paused));
+
+    h.tap_key(KeyCode::Escape);
+    for (track, paused) in paused(&h) {
+        assert_eq!(paused, track != Track::Music, "{:?}", track);
+    }
+    h.click(FIRST_ITEM);
+    assert!(paused(&h).iter().all(|(_, paused)| !paused));
+
+    quit_to_menu(&mut h);
+    assert!(h.app.world().resource::<Mixer>().track(Track::Radio).paused);
+}
+
+#[test]
+fn pause_freezes_the_room() {
+    let mut h = Harness::new();
+    let mut player = h.app.world_mut().resource_mut::<Player>();
+    for action in [
+        Action::TakeKey,
+        Action::UnlockKeyDrawer,
+        Action::TakeMorseCodeTranslator,
+        Action::InstallMorseCodeTranslator,
+    ] {
+        assert!(player.flags.perform(action), "{:?}", action);
+    }
+    h.tap_key(KeyCode::Escape);
+    h.advance_time(KNOCK_WAIT);
+    assert_eq!(h.knocks_heard(), 0);
+    h.click(FIRST_ITEM);
+    assert_eq!(h.state(), GameState::Playing);
+    h.advance_time(KNOCK_WAIT);
+    assert_eq!(h.knocks_heard(), 1);
+}
+
+#[test]
+fn calls_wait_while_paused() {
+    let mut h = Harness::new();
+    h.tap_key(KeyCode::Escape);
+    h.advance_time(CALL_WAIT);
+    h.click(FIRST_ITEM);
+    h.advance(1);
+    let phone = h.app.world().resource::<PhoneLine>();
+    assert!(matches!(phone.state, LineState::OnHook));
+
+    h.advance_time(CALL_WAIT);
+    let phone = h.app.world().resource::<PhoneLine>();
+    assert!(matches!(phone.state, LineState::Incoming { .. }));
+}
+
+#[test]
+fn menu_continues_the_run() {
+    let mut h = Harness::new();
+    h.key_into(KeyCode::KeyD, SceneId::Door);
+    quit_to_menu(&mut h);
+    assert!(h.has_text("CONTINUE"));
+    assert!(h.has_text("NEW GAME"));
+
+    h.click(FIRST_ITEM);
+    assert_eq!(h.state(), GameState::Playing);
+    assert!(matches!(h.scene(), SceneState::Active(SceneId::Door)));
+}
+
+#[test]
+fn new_game_starts_over() {
+    let mut h = Harness::new();
+    let seed = h.solutions().seed;
+    let universe = h.solutions().universe;
+    assert!(h
+        .app
+        .world_mut()
+        .resource_mut::<Player>()
+        .flags
+        .perform(Action::TakeKey));
+    h.key_into(KeyCode::KeyD, SceneId::Door);
+    quit_to_menu(&mut h);
+
+    h.click(SECOND_ITEM);
+    assert_eq!(h.state(), GameState::Playing);
+    assert_eq!(h.player().flags, Flags::default());
+    // A whole new run, starting over from its first universe.
+    assert_ne!(h.solutions().seed, seed);
+    assert_eq!(h.solutions().universe, universe);
+    h.wait_for_scene(SceneId::Desk);
+}
@@ -0,0 +1,231 @@
+//! Headless harness for driving the game from tests: no window, no GPU, a
+//! fixed frame time and input injected through the same events winit sends.
+
+use std::time::Duration;
+
+use bevy::input::keyboard::{Key, KeyboardInput, NativeKey};
+use bevy::input::mouse::{MouseButtonInput, MouseScrollUnit, MouseWheel};
+use bevy::input::{ButtonState, InputPlugin};
+use bevy::prelude::*;
+use bevy::render::texture::ImageLoader;
+use bevy::state::app::StatesPlugin;
+use bevy::text::FontLoader;
+use bevy::time::TimeUpdateStrategy;
+
+use crate::components::GameRng;
+use crate::endings::Gallery;
+use crate::event_log::{GameplayEvent, GameplayLog};
+use crate::game_state::GameState;
+use crate::gamedata::SceneId;
+use crate::input::MousePosition;
+use crate::player::{Player, SceneState};
+use crate::settings::SettingsStore;
+use crate::solutions::Solutions;
+
+mod endings;
+mod hints;
+mod menu;
+mod playthrough;
+mod progress;
+mod radio;
+mod replay;
+mod settings;
+mod solutions;
+
+pub const FRAME_TIME: Duration = Duration::from_nanos(16_666_667);
+
+// Generous upper bound on how long a scene transition may take.
+const MAX_TRANSITION_FRAMES: u32 = 60;
+
+pub struct Harness {
+    pub app: App,
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            InputPlugin,
+            StatesPlugin,
+        ));
+        // Normally registered by the render, sprite and text plugins. The
+        // loaders are only announced, so images and fonts stay pending forever
+        // instead of failing to load.
+        app.init_asset::<Image>()
+            .init_asset::<Mesh>()
+            .init_asset::<ColorMaterial>()
+            .init_asset::<Font>()
+            .preregister_asset_loader::<ImageLoader>(&["png"])
+            .preregister_asset_loader::<FontLoader>(&["ttf"]);
+        app.add_plugins(crate::gameplay_plugin);
+        // Keeps the endings gallery and settings off the disk.
+        app.insert_resource(Gallery::default());
+        app.insert_resource(SettingsStore::default());
+        // Straight into a run, past the loading screen and main menu.
+        app.insert_state(GameState::Playing);
+        app.insert_resource(GameRng::seeded(0));
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(FRAME_TIME));
+
+        let mut harness = Self { app };
+        // Startup, then let the initial scene load.
+        harness.advance(2);
+        harness
+    }
+
+    pub fn player(&self) -> &Player {
+        self.app.world().resource::<Player>()
+    }
+
+    pub fn solutions(&self) -> &Solutions {
+        self.app.world().resource::<Solutions>()
+    }
+
+    /// How many rounds of knocking there have been at the door.
+    pub fn knocks_heard(&self) -> usize {
+        self.app
+            .world()
+            .resource::<GameplayLog>()
+            .entries
+            .iter()
+            .filter(|entry| entry.event == GameplayEvent::DoorKnocked)
+            .count()
+    }
+
+    pub fn state(&self) -> GameState {
+        *self.app.world().resource::<State<GameState>>().get()
+    }
+
+    pub fn scene(&self) -> SceneState {
+        self.player().scene.clone()
+    }
+
+    /// Whether any text on screen reads exactly `value`.
+    pub fn has_text(&self, value: &str) -> bool {
+        self.app
+            .world()
+            .iter_entities()
+            .filter_map(|entity| entity.get::<Text>())
+            .any(|text| text.sections.iter().any(|section| section.value == value))
+    }
+
+    pub fn advance(&mut self, frames: u32) {
+        for _ in 0..frames {
+            self.app.update();
+        }
+    }
+
+    pub fn advance_time(&mut self, duration: Duration) {
+        let frames = duration.as_nanos().div_ceil(FRAME_TIME.as_nanos());
+        self.advance(frames as u32);
+    }
+
+    /// Moves the cursor to `pos` in world coordinates and clicks there.
+    pub fn click(&mut self, pos: Vec2) {
+        self.app.world_mut().resource_mut::<MousePosition>().0 = pos;
+        self.send_mouse(ButtonState::Pressed);
+        self.advance(1);
+        self.send_mouse(ButtonState::Released);
+        self.advance(1);
+    }
+
+    /// Presses at `from`, moves to `to` with the button held, and lets go.
+    pub fn drag(&mut self, from: Vec2, to: Vec2) {
+        self.app.world_mut().resource_mut::<MousePosition>().0 = from;
+        self.send_mouse(ButtonState::Pressed);
+        self.advance(1);
+        self.app.world_mut().resource_mut::<MousePosition>().0 = to;
+        self.advance(1);
+        self.send_mouse(ButtonState::Released);
+        self.advance(1);
+    }
+
+    /// Keeps the button pressed at `pos` for `duration`.
+    pub fn hold(&mut self, pos: Vec2, duration: Duration) {
+        self.app.world_mut().resource_mut::<MousePosition>().0 = pos;
+        self.send_mouse(ButtonState::Pressed);
+        self.advance_time(duration);
+        self.send_mouse(ButtonState::Released);
+        self.advance(1);
+    }
+
+    /// Turns the mouse wheel by `lines` notches, up for positive.
+    pub fn scroll(&mut self, lines: f32) {
+        self.app.world_mut().send_event(MouseWheel {
+            unit: MouseScrollUnit::Line,
+            x: 0.0,
+            y: lines,
+            window: Entity::PLACEHOLDER,
+        });
+        self.advance(1);
+    }
+
+    pub fn tap_key(&mut self, key: KeyCode) {
+        self.send_key(key, ButtonState::Pressed);
+        self.advance(1);
+        self.send_key(key, ButtonState::Released);
+        self.advance(1);
+    }
+
+    /// Holds `keys` down until `done` returns true, failing after `max_frames`.
+    pub fn hold_keys_until(
+        &mut self,
+        keys: &[KeyCode],
+        max_frames: u32,
+        done: impl Fn(&Harness) -> bool,
+    ) {
+        for key in keys {
+            self.send_key(*key, ButtonState::Pressed);
+        }
+        let mut frames = 0;
+        while !done(self) {
+            assert!(frames < max_frames, "gave up holding {:?}", keys);
+            self.advance(1);
+            frames += 1;
+        }
+        for key in keys {
+            self.send_key(*key, ButtonState::Released);
+        }
+        self.advance(1);
+    }
+
+    /// Runs frames until `scene` is active and its items have spawned.
+    pub fn wait_for_scene(&mut self, scene: SceneId) {
+        for _ in 0..MAX_TRANSITION_FRAMES {
+            if matches!(self.scene(), SceneState::Active(active) if active == scene) {
+                self.advance(2);
+                return;
+            }
+            self.advance(1);
+        }
+        panic!("never reached {:?}, stuck in {:?}", scene, self.scene());
+    }
+
+    pub fn click_into(&mut self, pos: Vec2, scene: SceneId) {
+        self.click(pos);
+        self.wait_for_scene(scene);
+    }
+
+    pub fn key_into(&mut self, key: KeyCode, scene: SceneId) {
+        self.tap_key(key);
+        self.wait_for_scene(scene);
+    }
+
+    fn send_mouse(&mut self, state: ButtonState) {
+        self.app.world_mut().send_event(MouseButtonInput {
+            button: MouseButton::Left,
+            state,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+
+    fn send_key(&mut self, key_code: KeyCode, state: ButtonState) {
+        self.app.world_mut().send_event(KeyboardInput {
+            key_code,
+            logical_key: Key::Unidentified(NativeKey::Unidentified),
+            state,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+}
@@ -0,0 +1,683 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use super::Harness;
+use crate::game_state::GameState;
+use crate::gamedata::{SceneId, DOOR_DIGITS, PHONE_BUTTON_LABELS, PUZZLE_CHARACTERS};
+use crate::input::{MousePosition, DIGIT_KEYS, LETTER_KEYS};
+use crate::lighting::Lighting;
+use crate::phone::{LineState, PhoneLine, ANSWER_DELAY, RING_TIMEOUT};
+use crate::player::{LightbulbColor, Player, SceneState};
+use crate::progress::{Action, Flag};
+use crate::tv::TvPlayer;
+
+// Centres of the clickable areas, in world coordinates.
+const DESK_TV: Vec2 = Vec2::new(630.0, 20.0);
+const DESK_LAMP: Vec2 = Vec2::new(-455.0, 285.0);
+const DESK_RADIO: Vec2 = Vec2::new(-5.0, -30.0);
+const DESK_PHONE: Vec2 = Vec2::new(-575.0, -60.0);
+const DESK_TOP_DRAWER: Vec2 = Vec2::new(-664.0, -311.0);
+const DESK_BOTTOM_DRAWER: Vec2 = Vec2::new(-664.0, -450.0);
+const BULLETIN_BOARD_KEY: Vec2 = Vec2::new(-585.0, 347.0);
+const BULLETIN_BOARD_OUTLET: Vec2 = Vec2::new(255.0, -287.0);
+const KEYPAD_UP: [Vec2; 3] = [
+    Vec2::new(260.0, 357.0),
+    Vec2::new(339.0, 362.0),
+    Vec2::new(421.0, 367.0),
+];
+const KEYPAD_DRAWER_SURGE_PROTECTOR: Vec2 = Vec2::new(-121.0, 305.0);
+const LOCK_DRAWER_LOCK: Vec2 = Vec2::new(570.0, 357.0);
+const LOCK_DRAWER_RADIO_MODULE: Vec2 = Vec2::new(-100.0, 100.0);
+const LAMP_SOCKET: Vec2 = Vec2::new(350.0, -80.0);
+const LAMP_SWITCH: Vec2 = Vec2::new(140.0, -320.0);
+const LOCK_DRAWER_FLASHLIGHT: Vec2 = Vec2::new(450.0, -200.0);
+const DOOR_LOCK_WHEELS: [Vec2; 3] = [
+    Vec2::new(440.0, -230.0),
+    Vec2::new(500.0, -230.0),
+    Vec2::new(560.0, -230.0),
+];
+const DOOR_HANDLE: Vec2 = Vec2::new(650.0, -340.0);
+const DOOR_PEEPHOLE: Vec2 = Vec2::new(330.0, 140.0);
+const RADIO_MODULE_SLOT: Vec2 = Vec2::new(-480.0, 120.0);
+const RADIO_MORSE_PRESET: Vec2 = Vec2::new(-605.0, -47.0);
+const BEHIND_LETTERS: [Vec2; 6] = [
+    Vec2::new(-105.0, -209.0),
+    Vec2::new(-66.0, -209.0),
+    Vec2::new(-24.0, -209.0),
+    Vec2::new(20.0, -209.0),
+    Vec2::new(59.0, -209.0),
+    Vec2::new(100.0, -209.0),
+];
+const BEHIND_SWITCH: Vec2 = Vec2::new(-537.0, -37.0);
+const PHONE_HANDSET: Vec2 = Vec2::new(150.0, 355.0);
+const PHONE_BUTTONS: [Vec2; 9] = [
+    Vec2::new(-41.0, 30.0),
+    Vec2::new(142.0, 35.0),
+    Vec2::new(329.0, 28.0),
+    Vec2::new(-74.0, -138.0),
+    Vec2::new(134.0, -141.0),
+    Vec2::new(333.0, -159.0),
+    Vec2::new(-91.0, -345.0),
+    Vec2::new(135.0, -345.0),
+    Vec2::new(327.0, -356.0),
+];
+
+const TV_FRAMES: u32 = 2000;
+const MORSE_TRANSLATION_TIME: Duration = Duration::from_millis(7500);
+const SWITCH_PRESS_TIME: Duration = Duration::from_millis(700);
+const KNOCK_INTERVAL: Duration = Duration::from_secs(40);
+// Long enough for the longest code to be knocked out.
+const KNOCK_ROUND: Duration = Duration::from_secs(15);
+
+// Every letter wheel starts at the first letter and turns forwards.
+fn turns_to(letter: char) -> usize {
+    PUZZLE_CHARACTERS
+        .iter()
+        .position(|c| c.starts_with(letter))
+        .unwrap()
+}
+
+fn letter_key(letter: char) -> KeyCode {
+    LETTER_KEYS[(letter as u8 - b'A') as usize]
+}
+
+fn take_key(h: &mut Harness) {
+    h.key_into(KeyCode::KeyA, SceneId::BulletinBoard);
+    h.click(BULLETIN_BOARD_KEY);
+    assert!(h.player().has(Flag::HasKey));
+    h.key_into(KeyCode::KeyD, SceneId::Desk);
+}
+
+fn take_radio_module(h: &mut Harness) {
+    h.click_into(DESK_TOP_DRAWER, SceneId::LockDrawer);
+    h.click(LOCK_DRAWER_LOCK);
+    h.wait_for_scene(SceneId::LockDrawerSolved);
+    assert!(h.player().has(Flag::OpenedKeyDrawer));
+    h.click(LOCK_DRAWER_RADIO_MODULE);
+    h.wait_for_scene(SceneId::LockDrawerEmpty);
+    assert!(h.player().has(Flag::HasMorseCodeTranslator));
+    h.key_into(KeyCode::KeyS, SceneId::Desk);
+}
+
+fn take_surge_protector(h: &mut Harness) {
+    h.click_into(DESK_BOTTOM_DRAWER, SceneId::KeypadDrawer);
+    let code = h.solutions().keypad_code;
+    for (wheel, letter) in code.chars().enumerate() {
+        for _ in 0..turns_to(letter) {
+            h.click(KEYPAD_UP[wheel]);
+        }
+    }
+    h.wait_for_scene(SceneId::KeypadDrawerSolved);
+    assert!(h.player().has(Flag::OpenedKeypadDrawer));
+    h.click(KEYPAD_DRAWER_SURGE_PROTECTOR);
+    h.wait_for_scene(SceneId::KeypadDrawerEmpty);
+    assert!(h.player().has(Flag::HasSurgeProtector));
+    h.key_into(KeyCode::KeyS, SceneId::Desk);
+}
+
+fn install_surge_protector(h: &mut Harness) {
+    h.key_into(KeyCode::KeyA, SceneId::BulletinBoard);
+    h.click(BULLETIN_BOARD_OUTLET);
+    assert!(h.player().has(Flag::InstalledSurgeProtector));
+    h.key_into(KeyCode::KeyD, SceneId::Desk);
+}
+
+fn install_radio_module(h: &mut Harness) {
+    h.click_into(DESK_RADIO, SceneId::Radio);
+    h.click(RADIO_MODULE_SLOT);
+    assert!(h.player().has(Flag::InstalledMorseCodeTranslator));
+    assert!(!h.player().has(Flag::RightSpeakerBroken));
+    h.click(RADIO_MORSE_PRESET);
+    h.advance_time(MORSE_TRANSLATION_TIME);
+    let behind_code = h.solutions().behind_code;
+    assert!(h.has_text(behind_code));
+    h.key_into(KeyCode::KeyS, SceneId::Desk);
+}
+
+fn tv_player_position(h: &Harness) -> Vec3 {
+    h.app
+        .world()
+        .iter_entities()
+        .find(|entity| entity.contains::<TvPlayer>())
+        .and_then(|entity| entity.get::<Transform>())
+        .expect("no TV player")
+        .translation
+}
+
+// East, south and west through the screen edges opens the whirlpool in the
+// middle. Going up first keeps clear of the monster.
+fn win_tv_game(h: &mut Harness) {
+    h.click_into(DESK_TV, SceneId::Tv);
+    h.hold_keys_until(&[KeyCode::ArrowUp], TV_FRAMES, |h| {
+        tv_player_position(h).y > 60.0
+    });
+    let start_x = tv_player_position(h).x;
+    h.hold_keys_until(&[KeyCode::ArrowRight], TV_FRAMES, |h| {
+        tv_player_position(h).x < start_x
+    });
+    let start_y = tv_player_position(h).y;
+    h.hold_keys_until(&[KeyCode::ArrowDown], TV_FRAMES, |h| {
+        tv_player_position(h).y > start_y
+    });
+    let start_x = tv_player_position(h).x;
+    h.hold_keys_until(&[KeyCode::ArrowLeft], TV_FRAMES, |h| {
+        tv_player_position(h).x > start_x
+    });
+    h.hold_keys_until(&[KeyCode::ArrowLeft], TV_FRAMES, |h| {
+        tv_player_position(h).x <= 0.0
+    });
+    h.hold_keys_until(&[KeyCode::ArrowDown], TV_FRAMES, |h| {
+        h.player().lightbulb_unlock().is_some()
+    });
+
+    assert_eq!(h.player().lightbulb_unlock(), Some(LightbulbColor::Red));
+    h.key_into(KeyCode::KeyS, SceneId::Desk);
+}
+
+fn install_lightbulb(h: &mut Harness) {
+    h.click_into(DESK_LAMP, SceneId::Lamp);
+    assert_eq!(h.player().installed_lightbulb(), None);
+    h.click(LAMP_SOCKET);
+    assert_eq!(h.player().installed_lightbulb(), Some(LightbulbColor::Red));
+    assert_eq!(h.player().lamp_light(), None);
+    h.click(LAMP_SWITCH);
+    assert_eq!(h.player().lamp_light(), Some(LightbulbColor::Red));
+    h.key_into(KeyCode::KeyS, SceneId::Desk);
+}
+
+// Types the code rather than clicking through it, which also checks that
+// letters doubling as movement keys stay in the scene.
+fn pull_save_switch(h: &mut Harness) {
+    h.key_into(KeyCode::KeyS, SceneId::Behind);
+    let code = h.solutions().behind_code;
+    h.click(BEHIND_LETTERS[0]);
+    for letter in code.chars() {
+        h.tap_key(letter_key(letter));
+    }
+    assert!(matches!(h.scene(), SceneState::Active(SceneId::Behind)));
+    assert!(h.player().has(Flag::SaveSwitchUnlocked));
+    h.click(BEHIND_SWITCH);
+    h.advance_time(SWITCH_PRESS_TIME);
+    assert!(h.player().has(Flag::SaveSwitchPulled));
+}
+
+fn phone_button(digit: usize) -> Vec2 {
+    let button = PHONE_BUTTON_LABELS.iter().position(|label| *label == digit);
+    PHONE_BUTTONS[button.unwrap()]
+}
+
+fn dial_phone(h: &mut Harness) {
+    h.click_into(DESK_PHONE, SceneId::Phone);
+    h.click(PHONE_HANDSET);
+    let number = h.solutions().phone_number();
+    for digit in number {
+        h.click(PHONE_BUTTONS[digit]);
+    }
+    assert_eq!(h.player().dialed_numbers, number);
+    h.advance_time(ANSWER_DELAY);
+    // Keep this universe.
+    h.click(phone_button(1));
+    assert!(h.player().has(Flag::UniverseSaved));
+}
+
+fn line_state(h: &Harness) -> &LineState {
+    &h.app.world().resource::<PhoneLine>().state
+}
+
+// Everything but actually seeing the phone in `color` light.
+fn light_phone(h: &mut Harness, color: LightbulbColor) {
+    let mut player = h.app.world_mut().resource_mut::<Player>();
+    for action in [
+        Action::TakeKey,
+        Action::UnlockKeyDrawer,
+        Action::TakeMorseCodeTranslator,
+        Action::OpenKeypadDrawer,
+        Action::TakeSurgeProtector,
+        Action::InstallSurgeProtector,
+        Action::InstallMorseCodeTranslator,
+        Action::WinTv(color),
+        Action::InstallLightbulb(color),
+        Action::PullLampSwitch,
+    ] {
+        assert!(player.flags.perform(action), "{:?}", action);
+    }
+}
+
+#[test]
+fn starts_at_desk() {
+    let h = Harness::new();
+    assert!(matches!(h.scene(), SceneState::Active(SceneId::Desk)));
+}
+
+#[test]
+fn radio_module_without_protector_breaks_speaker() {
+    let mut h = Harness::new();
+    take_key(&mut h);
+    take_radio_module(&mut h);
+    h.click_into(DESK_RADIO, SceneId::Radio);
+    h.click(RADIO_MODULE_SLOT);
+    assert!(h.player().has(Flag::RightSpeakerBroken));
+}
+
+#[test]
+fn broken_speaker_gets_a_call() {
+    let mut h = Harness::new();
+    take_key(&mut h);
+    take_radio_module(&mut h);
+    h.click_into(DESK_RADIO, SceneId::Radio);
+    h.click(RADIO_MODULE_SLOT);
+    h.advance(1);
+    assert!(h.app.world().resource::<PhoneLine>().needs_attention());
+
+    h.key_into(KeyCode::KeyS, SceneId::Desk);
+    h.click_into(DESK_PHONE, SceneId::Phone);
+    assert!(h.has_text("INCOMING"));
+    h.click(PHONE_HANDSET);
+    assert!(h.has_text("SWITCHBOARD."));
+}
+
+#[test]
+fn missed_call_plays_back() {
+    let mut h = Harness::new();
+    take_key(&mut h);
+    take_radio_module(&mut h);
+    h.click_into(DESK_RADIO, SceneId::Radio);
+    h.click(RADIO_MODULE_SLOT);
+    h.advance_time(RING_TIMEOUT);
+    let phone = h.app.world().resource::<PhoneLine>();
+    assert!(matches!(phone.state, LineState::OnHook));
+    assert_eq!(phone.missed.len(), 1);
+
+    h.key_into(KeyCode::KeyS, SceneId::Desk);
+    h.click_into(DESK_PHONE, SceneId::Phone);
+    assert!(h.has_text("1 MISSED"));
+    h.click(PHONE_HANDSET);
+    assert!(h.has_text("SWITCHBOARD."));
+    assert!(h.app.world().resource::<PhoneLine>().missed.is_empty());
+}
+
+#[test]
+fn full_solution() {
+    let mut h = Harness::new();
+    take_key(&mut h);
+    take_radio_module(&mut h);
+    take_surge_protector(&mut h);
+    install_surge_protector(&mut h);
+    install_radio_module(&mut h);
+    win_tv_game(&mut h);
+    install_lightbulb(&mut h);
+    dial_phone(&mut h);
+}
+
+#[test]
+fn save_switch_ending() {
+    let mut h = Harness::new();
+    take_key(&mut h);
+    take_radio_module(&mut h);
+    take_surge_protector(&mut h);
+    install_surge_protector(&mut h);
+    install_radio_module(&mut h);
+    pull_save_switch(&mut h);
+}
+
+#[test]
+fn restart_switch_resets_the_universe() {
+    let mut h = Harness::new();
+    take_key(&mut h);
+    h.key_into(KeyCode::KeyS, SceneId::Behind);
+    h.click(BEHIND_SWITCH);
+    h.advance_time(SWITCH_PRESS_TIME);
+    h.wait_for_scene(SceneId::Desk);
+    assert!(!h.player().has(Flag::HasKey));
+    assert_eq!(h.solutions().universe, 2);
+}
+
+#[test]
+fn door_jams_when_brute_forced() {
+    const SETTLE_TIME: Duration = Duration::from_millis(1600);
+    const JAM_TIME: Duration = Duration::from_secs(10);
+
+    let mut h = Harness::new();
+    h.key_into(KeyCode::KeyD, SceneId::Door);
+    // Keep the middle wheel wrong, so that turning the first never opens it.
+    let middle = h.solutions().door_code.chars().nth(1).unwrap();
+    if DOOR_DIGITS[0].starts_with(middle) {
+        h.click(DOOR_LOCK_WHEELS[1]);
+    }
+    for _ in 0..5 {
+        h.click(DOOR_LOCK_WHEELS[0]);
+        h.advance_time(SETTLE_TIME);
+    }
+    let jammed_at = h.player().door_puzzle_state;
+    h.click(DOOR_LOCK_WHEELS[0]);
+    assert_eq!(h.player().door_puzzle_state, jammed_at);
+
+    h.advance_time(JAM_TIME);
+    h.click(DOOR_LOCK_WHEELS[0]);
+    assert_ne!(h.player().door_puzzle_state, jammed_at);
+}
+
+#[test]
+fn keypad_accepts_typed_code() {
+    let mut h = Harness::new();
+    h.click_into(DESK_BOTTOM_DRAWER, SceneId::KeypadDrawer);
+    // Turns the first wheel once, which also focuses it.
+    h.click(KEYPAD_UP[0]);
+    h.tap_key(KeyCode::Backspace);
+    let code = h.solutions().keypad_code;
+    for letter in code.chars() {
+        h.tap_key(letter_key(letter));
+    }
+    h.wait_for_scene(SceneId::KeypadDrawerSolved);
+    assert!(h.player().has(Flag::OpenedKeypadDrawer));
+}
+
+#[test]
+fn escape_hands_the_keyboard_back() {
+    let mut h = Harness::new();
+    h.key_into(KeyCode::KeyS, SceneId::Behind);
+    h.click(BEHIND_LETTERS[2]);
+    h.tap_key(KeyCode::KeyD);
+    assert_eq!(h.player().behind_puzzle_state[2], turns_to('D'));
+    h.tap_key(KeyCode::Escape);
+    h.key_into(KeyCode::KeyS, SceneId::Desk);
+}
+
+#[test]
+fn phone_accepts_typed_number() {
+    let mut h = Harness::new();
+    light_phone(&mut h, LightbulbColor::Red);
+    h.click_into(DESK_PHONE, SceneId::Phone);
+    h.click(PHONE_HANDSET);
+    let number = h.solutions().phone_number();
+    for button in number {
+        h.tap_key(DIGIT_KEYS[PHONE_BUTTON_LABELS[button]]);
+    }
+    assert_eq!(h.player().dialed_numbers, number);
+    h.advance_time(ANSWER_DELAY);
+    h.tap_key(KeyCode::Digit1);
+    assert!(h.player().has(Flag::UniverseSaved));
+}
+
+#[test]
+fn switchboard_can_abandon_the_universe() {
+    let mut h = Harness::new();
+    light_phone(&mut h, LightbulbColor::Red);
+    h.click_into(DESK_PHONE, SceneId::Phone);
+    h.click(PHONE_HANDSET);
+    for button in h.solutions().phone_number() {
+        h.click(PHONE_BUTTONS[button]);
+    }
+    h.advance_time(ANSWER_DELAY);
+    assert!(h.has_text("..."));
+
+    // Digits without a branch are ignored.
+    h.click(phone_button(5));
+    h.click(phone_button(2));
+    assert!(h.player().has(Flag::UniverseAbandoned));
+    assert!(!h.player().has(Flag::UniverseSaved));
+    // The ending takes over from the call.
+    h.advance(1);
+    assert_eq!(h.state(), GameState::Ending);
+}
+
+#[test]
+fn green_light_reaches_the_other_side() {
+    let mut h = Harness::new();
+    light_phone(&mut h, LightbulbColor::Green);
+    h.click_into(DESK_PHONE, SceneId::Phone);
+    h.click(PHONE_HANDSET);
+    for button in h.solutions().phone_number() {
+        h.click(PHONE_BUTTONS[button]);
+    }
+    h.advance_time(ANSWER_DELAY);
+    assert!(h.has_text("SWITCHBOARD."));
+    h.click(phone_button(1));
+    assert!(!h.player().has(Flag::UniverseSaved));
+
+    h.click(PHONE_HANDSET);
+    h.click(PHONE_HANDSET);
+    for button in h.solutions().phone_number().into_iter().rev() {
+        h.click(PHONE_BUTTONS[button]);
+    }
+    h.advance_time(ANSWER_DELAY);
+    h.click(phone_button(1));
+    assert!(h.player().has(Flag::UniverseTraded));
+    assert!(!h.player().has(Flag::UniverseSaved));
+}
+
+#[test]
+fn hidden_writing_needs_the_right_light() {
+    const BOARD_WRITING: &str = "THE STATION\nKNOWS THE NUMBER";
+    const DESK_MARKS: &str = "<< 9 8 7 6 5 4 3 2 1";
+
+    let mut h = Harness::new();
+    light_phone(&mut h, LightbulbColor::Red);
+    h.key_into(KeyCode::KeyA, SceneId::BulletinBoard);
+    assert!(h.has_text(BOARD_WRITING));
+    h.key_into(KeyCode::KeyD, SceneId::Desk);
+    assert!(!h.has_text(DESK_MARKS));
+
+    let mut h = Harness::new();
+    light_phone(&mut h, LightbulbColor::Green);
+    h.key_into(KeyCode::KeyA, SceneId::BulletinBoard);
+    assert!(!h.has_text(BOARD_WRITING));
+    h.key_into(KeyCode::KeyD, SceneId::Desk);
+    assert!(h.has_text(DESK_MARKS));
+}
+
+fn lighting(h: &Harness) -> &Lighting {
+    h.app.world().resource::<Lighting>()
+}
+
+#[test]
+fn lamp_lights_the_room() {
+    let mut h = Harness::new();
+    let dark = lighting(&h).ambient;
+    assert!(lighting(&h).lamp.is_none());
+
+    light_phone(&mut h, LightbulbColor::Red);
+    h.advance(1);
+    assert!(lighting(&h).ambient > dark);
+    let lamp = lighting(&h).lamp.expect("the desk shows the lamp");
+    assert!(lamp.color.red > lamp.color.green);
+
+    // Other scenes only get what spills over.
+    h.key_into(KeyCode::KeyA, SceneId::BulletinBoard);
+    assert!(lighting(&h).lamp.is_none());
+    assert!(lighting(&h).ambient > dark);
+}
+
+#[test]
+fn socket_swaps_bulbs() {
+    let mut h = Harness::new();
+    let mut player = h.app.world_mut().resource_mut::<Player>();
+    player.flags.perform(Action::WinTv(LightbulbColor::Red));
+    h.click_into(DESK_LAMP, SceneId::Lamp);
+    h.click(LAMP_SOCKET);
+    h.click(LAMP_SWITCH);
+    assert_eq!(h.player().lamp_light(), Some(LightbulbColor::Red));
+
+    let mut player = h.app.world_mut().resource_mut::<Player>();
+    player.flags.perform(Action::WinTv(LightbulbColor::Green));
+    h.click(LAMP_SOCKET);
+    assert_eq!(h.player().lamp_light(), Some(LightbulbColor::Green));
+    h.click(LAMP_SWITCH);
+    assert_eq!(h.player().lamp_light(), None);
+}
+
+#[test]
+fn flashlight_follows_the_cursor() {
+    let mut h = Harness::new();
+    take_key(&mut h);
+    take_radio_module(&mut h);
+    assert!(lighting(&h).flashlight.is_none());
+
+    h.click_into(DESK_TOP_DRAWER, SceneId::LockDrawerEmpty);
+    h.click(LOCK_DRAWER_FLASHLIGHT);
+    assert!(h.player().has(Flag::HasFlashlight));
+    let cursor = Vec2::new(-300.0, 120.0);
+    h.app.world_mut().resource_mut::<MousePosition>().0 = cursor;
+    h.advance(1);
+    assert_eq!(
+        lighting(&h).flashlight.map(|light| light.position),
+        Some(cursor)
+    );
+}
+
+#[test]
+fn blown_speaker_flickers_the_lights() {
+    let mut h = Harness::new();
+    let mut player = h.app.world_mut().resource_mut::<Player>();
+    for action in [
+        Action::TakeKey,
+        Action::UnlockKeyDrawer,
+        Action::TakeMorseCodeTranslator,
+        Action::WinTv(LightbulbColor::Red),
+        Action::InstallLightbulb(LightbulbColor::Red),
+        Action::PullLampSwitch,
+    ] {
+        assert!(player.flags.perform(action), "{:?}", action);
+    }
+    h.click_into(DESK_RADIO, SceneId::Radio);
+    let lit = lighting(&h).ambient;
+    h.click(RADIO_MODULE_SLOT);
+    assert!(h.player().has(Flag::RightSpeakerBroken));
+
+    let mut darkest = lit;
+    for _ in 0..30 {
+        h.advance(1);
+        darkest = darkest.min(lighting(&h).ambient);
+    }
+    assert!(darkest < lit);
+    h.advance_time(Duration::from_secs(2));
+    assert_eq!(lighting(&h).ambient, lit);
+}
+
+#[test]
+fn keypad_is_dead_on_the_hook() {
+    let mut h = Harness::new();
+    light_phone(&mut h, LightbulbColor::Red);
+    h.click_into(DESK_PHONE, SceneId::Phone);
+    h.click(PHONE_BUTTONS[0]);
+    assert!(h.player().dialed_numbers.is_empty());
+}
+
+#[test]
+fn wrong_number_never_connects() {
+    let mut h = Harness::new();
+    light_phone(&mut h, LightbulbColor::Red);
+    h.click_into(DESK_PHONE, SceneId::Phone);
+    h.click(PHONE_HANDSET);
+    let mut number = h.solutions().phone_number();
+    number[0] = (number[0] + 1) % PHONE_BUTTONS.len();
+    for button in number {
+        h.click(PHONE_BUTTONS[button]);
+    }
+    h.advance_time(ANSWER_DELAY * 2);
+    assert!(matches!(
+        line_state(&h),
+        LineState::Busy | LineState::Ringing { callee: None, .. }
+    ));
+    assert!(!h.player().has(Flag::UniverseSaved));
+
+    h.key_into(KeyCode::KeyS, SceneId::Desk);
+    assert!(matches!(line_state(&h), LineState::OnHook));
+}
+
+#[test]
+fn knocking_starts_with_the_radio() {
+    let mut h = Harness::new();
+    h.advance_time(KNOCK_INTERVAL);
+    assert_eq!(h.knocks_heard(), 0);
+    take_key(&mut h);
+    take_radio_module(&mut h);
+    h.click_into(DESK_RADIO, SceneId::Radio);
+    h.click(RADIO_MODULE_SLOT);
+    assert!(h.player().has(Flag::InstalledMorseCodeTranslator));
+    h.advance_time(KNOCK_INTERVAL);
+    assert_eq!(h.knocks_heard(), 1);
+}
+
+#[test]
+fn peephole_backs_out_to_the_door() {
+    let mut h = Harness::new();
+    h.key_into(KeyCode::KeyD, SceneId::Door);
+    h.click_into(DOOR_PEEPHOLE, SceneId::Peephole);
+    h.key_into(KeyCode::KeyS, SceneId::Door);
+}
+
+#[test]
+fn escape_through_the_door() {
+    let mut h = Harness::new();
+    let code = h.solutions().door_code;
+    let set_code = |h: &mut Harness| {
+        for (wheel, digit) in code.chars().enumerate() {
+            let turns = DOOR_DIGITS
+                .iter()
+                .position(|d| d.starts_with(digit))
+                .unwrap();
+            for _ in 0..turns {
+                h.click(DOOR_LOCK_WHEELS[wheel]);
+            }
+        }
+    };
+
+    // Nobody has drawn the bolt on the other side yet.
+    h.key_into(KeyCode::KeyD, SceneId::Door);
+    set_code(&mut h);
+    assert!(!h.player().has(Flag::DoorUnlocked));
+    h.click(DOOR_HANDLE);
+    assert!(!h.player().has(Flag::Escaped));
+
+    h.key_into(KeyCode::KeyA, SceneId::Desk);
+    take_key(&mut h);
+    take_radio_module(&mut h);
+    h.click_into(DESK_TOP_DRAWER, SceneId::LockDrawerEmpty);
+    h.click(LOCK_DRAWER_FLASHLIGHT);
+    h.key_into(KeyCode::KeyS, SceneId::Desk);
+    h.key_into(KeyCode::KeyD, SceneId::Door);
+    h.click(DOOR_HANDLE);
+    assert!(!h.player().has(Flag::Escaped));
+
+    h.key_into(KeyCode::KeyA, SceneId::Desk);
+    h.click_into(DESK_RADIO, SceneId::Radio);
+    h.click(RADIO_MODULE_SLOT);
+    h.advance_time(KNOCK_INTERVAL + KNOCK_ROUND);
+    assert_eq!(h.knocks_heard(), 1);
+    assert!(h.player().has(Flag::DoorUnlocked));
+
+    h.key_into(KeyCode::KeyS, SceneId::Desk);
+    h.key_into(KeyCode::KeyD, SceneId::Door);
+    h.click(DOOR_HANDLE);
+    assert!(h.player().has(Flag::Escaped));
+}
+
+#[test]
+fn door_code_after_the_knocks() {
+    let mut h = Harness::new();
+    take_key(&mut h);
+    take_radio_module(&mut h);
+    h.click_into(DESK_RADIO, SceneId::Radio);
+    h.click(RADIO_MODULE_SLOT);
+    h.advance_time(KNOCK_INTERVAL + KNOCK_ROUND);
+    assert!(h.player().has(Flag::HeardKnocks));
+    assert!(!h.player().has(Flag::DoorUnlocked));
+
+    h.key_into(KeyCode::KeyS, SceneId::Desk);
+    h.key_into(KeyCode::KeyD, SceneId::Door);
+    for (wheel, digit) in h.solutions().door_code.chars().enumerate() {
+        let turns = DOOR_DIGITS
+            .iter()
+            .position(|d| d.starts_with(digit))
+            .unwrap();
+        for _ in 0..turns {
+            h.click(DOOR_LOCK_WHEELS[wheel]);
+        }
+    }
+    assert!(h.player().has(Flag::DoorUnlocked));
+    h.click(DOOR_HANDLE);
+    assert!(h.has_text("TOO DARK OUT THERE\nTO GO WITHOUT A LIGHT."));
+    assert!(!h.player().has(Flag::Escaped));
+}
@@ -0,0 +1,109 @@
+use crate::player::LightbulbColor;
+use crate::progress::{analyze, Action, Clue, Clues, Flag, Flags};
+
+#[test]
+fn every_ending_is_reachable() {
+    let analysis = analyze();
+    assert!(analysis.reachable_states > 1);
+    assert_eq!(analysis.unreachable_endings, vec![]);
+}
+
+#[test]
+fn no_softlocks() {
+    assert_eq!(analyze().softlocks, vec![]);
+}
+
+#[test]
+fn only_run_long_answers_survive_a_reset() {
+    let mut clues = Clues::default();
+    for clue in [
+        Clue::KeypadCode,
+        Clue::BehindCode,
+        Clue::PhoneNumber,
+        Clue::KnockCode,
+    ] {
+        clues.insert(clue);
+    }
+    let after = clues.after_reset();
+    assert!(after.contains_all(&[Clue::KeypadCode, Clue::KnockCode]));
+    assert!(!after.contains_any(&[Clue::BehindCode, Clue::PhoneNumber]));
+}
+
+#[test]
+fn key_drawer_needs_key() {
+    let mut flags = Flags::default();
+    assert!(!flags.perform(Action::UnlockKeyDrawer));
+    assert!(flags.perform(Action::TakeKey));
+    assert!(flags.perform(Action::UnlockKeyDrawer));
+    assert!(flags.contains(Flag::OpenedKeyDrawer));
+}
+
+#[test]
+fn unprotected_module_breaks_speaker() {
+    let mut flags = Flags::default();
+    for action in [
+        Action::TakeKey,
+        Action::UnlockKeyDrawer,
+        Action::TakeMorseCodeTranslator,
+        Action::InstallMorseCodeTranslator,
+    ] {
+        assert!(flags.perform(action), "{:?} refused", action);
+    }
+    assert!(flags.contains(Flag::RightSpeakerBroken));
+}
+
+#[test]
+fn phone_needs_red_light() {
+    let mut flags = Flags::default();
+    assert!(!flags.perform(Action::DialPhone));
+    flags.perform(Action::WinTv(LightbulbColor::Green));
+    flags.perform(Action::InstallLightbulb(LightbulbColor::Green));
+    flags.perform(Action::PullLampSwitch);
+    assert!(!flags.perform(Action::DialPhone));
+    flags.perform(Action::WinTv(LightbulbColor::Red));
+    flags.perform(Action::InstallLightbulb(LightbulbColor::Red));
+    assert!(flags.perform(Action::DialPhone));
+}
+
+#[test]
+fn phone_needs_the_lamp_on() {
+    let mut flags = Flags::default();
+    flags.perform(Action::WinTv(LightbulbColor::Red));
+    flags.perform(Action::InstallLightbulb(LightbulbColor::Red));
+    assert!(!flags.perform(Action::DialPhone));
+    assert!(flags.perform(Action::PullLampSwitch));
+    assert!(flags.perform(Action::PullLampSwitch));
+    assert!(!flags.perform(Action::DialPhone));
+    flags.perform(Action::PullLampSwitch);
+    assert!(flags.perform(Action::DialPhone));
+}
+
+#[test]
+fn trading_needs_green_light() {
+    let mut flags = Flags::default();
+    flags.perform(Action::WinTv(LightbulbColor::Red));
+    flags.perform(Action::InstallLightbulb(LightbulbColor::Red));
+    flags.perform(Action::PullLampSwitch);
+    assert!(!flags.perform(Action::TradeUniverse));
+    flags.perform(Action::WinTv(LightbulbColor::Green));
+    flags.perform(Action::InstallLightbulb(LightbulbColor::Green));
+    assert!(!flags.perform(Action::DialPhone));
+    assert!(flags.perform(Action::TradeUniverse));
+}
+
+#[test]
+fn escaping_needs_the_door_open_and_a_light() {
+    let mut flags = Flags::default();
+    assert!(!flags.perform(Action::UnlockDoor));
+    flags.perform(Action::TakeKey);
+    flags.perform(Action::UnlockKeyDrawer);
+    flags.perform(Action::TakeMorseCodeTranslator);
+    flags.perform(Action::InstallMorseCodeTranslator);
+    assert!(!flags.perform(Action::UnlockDoor));
+    assert!(flags.perform(Action::ListenToKnocks));
+    assert!(flags.perform(Action::UnlockDoor));
+    assert!(!flags.perform(Action::OpenDoor));
+    flags.perform(Action::TakeFlashlight);
+    assert!(flags.perform(Action::OpenDoor));
+    assert!(flags.contains(Flag::Escaped));
+}
@@ -0,0 +1,242 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use super::Harness;
+use crate::audio::{reception, RadioAudio};
+use crate::detuning::{mix, muffle, whistle_level, whistle_playback_rate};
+use crate::gamedata::{AmRadioFreq, PresetAmRadioFreq, SceneId};
+use crate::morse::keying;
+use crate::player::Player;
+use crate::progress::Action;
+use crate::radio_static::{crackle, hiss, levels};
+
+const DESK_RADIO: Vec2 = Vec2::new(-5.0, -30.0);
+const DIAL_MIDDLE: Vec2 = Vec2::new(285.0, 125.0);
+const DIAL_RIGHT_END: Vec2 = Vec2::new(680.0, 125.0);
+const TUNE_DOWN: Vec2 = Vec2::new(0.0, -170.0);
+const TUNE_UP: Vec2 = Vec2::new(215.0, -170.0);
+const MORSE_TRANSLATION_TIME: Duration = Duration::from_millis(7500);
+
+fn freq(h: &mut Harness) -> i32 {
+    h.app
+        .world_mut()
+        .query_filtered::<&AmRadioFreq, With<RadioAudio>>()
+        .single(h.app.world())
+        .0
+}
+
+#[test]
+fn dial_drags_across_the_band() {
+    let mut h = Harness::new();
+    h.click_into(DESK_RADIO, SceneId::Radio);
+    assert!(h.has_text("600 kHz"));
+
+    h.drag(DIAL_MIDDLE, DIAL_RIGHT_END);
+    assert_eq!(freq(&mut h), AmRadioFreq::MAX);
+    assert!(h.has_text("1700 kHz"));
+
+    // Dragging past the end of the scale stops at the end of the band.
+    h.drag(DIAL_MIDDLE, Vec2::new(-900.0, 125.0));
+    assert_eq!(freq(&mut h), AmRadioFreq::MIN);
+    assert!(h.has_text("530 kHz"));
+}
+
+#[test]
+fn arrows_fine_tune() {
+    let mut h = Harness::new();
+    h.click_into(DESK_RADIO, SceneId::Radio);
+    h.click(TUNE_UP);
+    h.click(TUNE_UP);
+    assert_eq!(freq(&mut h), 602);
+    h.click(TUNE_DOWN);
+    assert_eq!(freq(&mut h), 601);
+
+    // Holding an arrow keeps going.
+    h.hold(TUNE_DOWN, Duration::from_secs(1));
+    assert!((585..595).contains(&freq(&mut h)), "{}", freq(&mut h));
+}
+
+#[test]
+fn wheel_tunes_only_on_the_radio() {
+    let mut h = Harness::new();
+    h.scroll(3.0);
+    assert_eq!(freq(&mut h), 600);
+
+    h.click_into(DESK_RADIO, SceneId::Radio);
+    h.scroll(3.0);
+    assert_eq!(freq(&mut h), 615);
+    h.scroll(-1.0);
+    assert_eq!(freq(&mut h), 610);
+    h.advance(1);
+    assert!(h.has_text("610 kHz"));
+}
+
+#[test]
+fn translator_decodes_near_the_station() {
+    let mut h = Harness::new();
+    let mut player = h.app.world_mut().resource_mut::<Player>();
+    for action in [
+        Action::TakeKey,
+        Action::UnlockKeyDrawer,
+        Action::TakeMorseCodeTranslator,
+        Action::InstallMorseCodeTranslator,
+    ] {
+        assert!(player.flags.perform(action), "{:?}", action);
+    }
+    h.click_into(DESK_RADIO, SceneId::Radio);
+    // 5 kHz above the station.
+    h.scroll(11.0);
+    h.advance_time(MORSE_TRANSLATION_TIME);
+    // Without the surge protector the speaker blew, which changes the code.
+    let keypad_code = h.solutions().keypad_code;
+    assert!(h.has_text(keypad_code));
+}
+
+#[test]
+fn morse_keys_the_word() {
+    let tones: Vec<usize> = keying("SOS")
+        .into_iter()
+        .filter(|(on, _)| *on)
+        .map(|(_, units)| units)
+        .collect();
+    assert_eq!(tones, [1, 1, 1, 3, 3, 3, 1, 1, 1]);
+    // Letters are three units apart, and the word ends in a long pause
+    // before it repeats.
+    let runs = keying("ET");
+    assert_eq!(runs[..3], [(true, 1), (false, 3), (true, 3)]);
+    assert!(runs.last().unwrap().1 > 3);
+}
+
+fn rms(samples: impl Iterator<Item = f32>) -> f32 {
+    let (sum, count) = samples.fold((0.0, 0), |(sum, count), s| (sum + s * s, count + 1));
+    (sum / count as f32).sqrt()
+}
+
+#[test]
+fn hiss_is_band_limited_and_loops() {
+    let samples = hiss(&mut StdRng::seed_from_u64(0));
+    let steps = || samples.windows(2).map(|pair| pair[1] - pair[0]);
+    // White noise changes by more from one sample to the next than it holds.
+    assert!(rms(steps()) < 0.9 * rms(samples.iter().copied()));
+
+    let largest_step = steps().fold(0.0f32, |max, step| max.max(step.abs()));
+    let seam = samples[samples.len() - 1] - samples[0];
+    assert!(seam.abs() <= largest_step, "{} > {}", seam, largest_step);
+}
+
+#[test]
+fn crackle_is_mostly_quiet() {
+    let samples = crackle(&mut StdRng::seed_from_u64(0));
+    let audible = samples.iter().filter(|s| s.abs() > 0.01).count();
+    assert!(audible > 0);
+    assert!(audible < samples.len() / 2, "{}/{}", audible, samples.len());
+}
+
+#[test]
+fn static_follows_the_dial() {
+    let between = levels(900, 0.0);
+    let on_station = levels(900, 1.0);
+    assert!(on_station.hiss < between.hiss);
+    assert_eq!(on_station.crackle, 0.0);
+
+    let bottom = levels(AmRadioFreq::MIN, 0.0);
+    let top = levels(AmRadioFreq::MAX, 0.0);
+    assert!(bottom.crackle > top.crackle);
+    assert!(bottom.playback_rate < top.playback_rate);
+}
+
+#[test]
+fn detuning_muffles_then_distorts() {
+    let centre = mix(0);
+    assert_eq!(centre.clear, 1.0);
+    assert_eq!(centre.distorted, 0.0);
+
+    let slightly_off = mix(-8);
+    assert!(slightly_off.muffled > 0.0 && slightly_off.clear > 0.0);
+    assert_eq!(slightly_off.distorted, 0.0);
+
+    let edge = mix(30);
+    assert_eq!(edge.distorted, 1.0);
+    for delta in 0..=40 {
+        let shares = mix(delta);
+        let total = shares.clear + shares.muffled + shares.distorted;
+        assert!((total - 1.0).abs() < 1e-9, "{} at {}", total, delta);
+    }
+}
+
+#[test]
+fn overlapping_stations_are_heard_together() {
+    let news = AmRadioFreq::from(PresetAmRadioFreq::News);
+    let morse = AmRadioFreq::from(PresetAmRadioFreq::Morse);
+    // Halfway between the two, both come in and neither wins.
+    let between = reception(630, &[news, morse]);
+    let [a, b] = between.stations[..] else {
+        panic!("{:?}", between);
+    };
+    assert!(a.volume > 0.0);
+    assert_eq!(a, b);
+    assert!(between.signal > reception(630, &[news]).signal);
+
+    // Closer to one, it captures the receiver and breaks the other up.
+    let near_news = reception(622, &[news, morse]);
+    let [news_heard, morse_heard] = near_news.stations[..] else {
+        panic!("{:?}", near_news);
+    };
+    assert!(news_heard.interference < morse_heard.interference);
+    assert!(news_heard.volume > morse_heard.volume);
+    assert!(morse_heard.volume < reception(622, &[morse]).stations[0].volume);
+    let clean = mix(12);
+    let interfered = clean.interfered(morse_heard.interference);
+    assert!(interfered.distorted > clean.distorted);
+    let total = interfered.clear + interfered.muffled + interfered.distorted;
+    assert!((total - 1.0).abs() < 1e-9, "{}", total);
+}
+
+#[test]
+fn reception_ignores_station_order() {
+    let stations = [
+        PresetAmRadioFreq::Morse,
+        PresetAmRadioFreq::Music,
+        PresetAmRadioFreq::News,
+        PresetAmRadioFreq::Numbers,
+    ]
+    .map(AmRadioFreq::from);
+    let mut reversed = stations;
+    reversed.reverse();
+    for freq in (AmRadioFreq::MIN..=800).step_by(3) {
+        let forward = reception(freq, &stations);
+        let mut backward = reception(freq, &reversed);
+        backward.stations.reverse();
+        assert_eq!(forward, backward, "{} kHz", freq);
+    }
+}
+
+#[test]
+fn whistle_zero_beats_on_the_station() {
+    assert_eq!(whistle_level(0), 0.0);
+    assert!(whistle_level(1) < whistle_level(5));
+    assert_eq!(whistle_playback_rate(-4), whistle_playback_rate(4));
+    assert!(whistle_playback_rate(4) < whistle_playback_rate(20));
+}
+
+#[test]
+fn muffled_copy_loses_the_highs() {
+    // As high as a tone gets.
+    let shrill = StaticSoundData {
+        sample_rate: 44_100,
+        frames: (0..44_100)
+            .map(|i| Frame::from_mono(if i % 2 == 0 { 1.0 } else { -1.0 }))
+            .collect(),
+        settings: StaticSoundSettings::default(),
+    };
+    let muffled = muffle(&shrill);
+    assert!(muffled.sample_rate < shrill.sample_rate);
+    // A second of sound is still a second.
+    assert_eq!(muffled.duration(), shrill.duration());
+    let loudness = |sound: &StaticSoundData| rms(sound.frames.iter().map(|frame| frame.left));
+    assert!(loudness(&muffled) < 0.1 * loudness(&shrill));
+}
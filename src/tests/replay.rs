@@ -0,0 +1,28 @@
+use std::path::Path;
+
+use crate::replay::ReplayMode;
+
+fn args(args: &[&str]) -> impl Iterator<Item = String> {
+    args.iter()
+        .map(|arg| arg.to_string())
+        .collect::<Vec<_>>()
+        .into_iter()
+}
+
+#[test]
+fn record_flag_anywhere_in_the_args() {
+    for line in [
+        &["--record", "out.ron"][..],
+        &["--seed", "ABC", "--record", "out.ron"],
+        &["--windowed", "--record", "out.ron"],
+    ] {
+        let ReplayMode::Recording { path, .. } = ReplayMode::from_args(args(line)) else {
+            panic!("{:?} didn't record", line);
+        };
+        assert_eq!(path, Path::new("out.ron"));
+    }
+    assert!(matches!(
+        ReplayMode::from_args(args(&["--seed", "--record"])),
+        ReplayMode::Off
+    ));
+}
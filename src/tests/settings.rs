@@ -0,0 +1,127 @@
+use bevy::prelude::*;
+
+use super::menu::SECOND_ITEM;
+use super::Harness;
+use crate::game_state::GameState;
+use crate::mixer::{Mixer, Track};
+use crate::settings::{Settings, SettingsStore, TextSize, Volume};
+
+// Rows on the settings screen, at the middle of their slider or value.
+const RADIO_SLIDER: Vec2 = Vec2::new(250.0, 300.0);
+const SFX_SLIDER: Vec2 = Vec2::new(125.0, 140.0);
+const RADIO_LEVEL: Vec2 = Vec2::new(650.0, 300.0);
+const TEXT_SIZE: Vec2 = Vec2::new(250.0, -180.0);
+const REDUCED_MOTION: Vec2 = Vec2::new(250.0, -260.0);
+const BACK: Vec2 = Vec2::new(0.0, -400.0);
+
+fn open_settings(h: &mut Harness) {
+    h.tap_key(KeyCode::Escape);
+    h.click(SECOND_ITEM);
+    assert_eq!(h.state(), GameState::Settings);
+}
+
+fn settings(h: &Harness) -> &Settings {
+    h.app.world().resource::<Settings>()
+}
+
+#[test]
+fn slider_sets_the_volume() {
+    let mut h = Harness::new();
+    open_settings(&mut h);
+    assert!(h.has_text("100%"));
+
+    h.click(RADIO_SLIDER);
+    assert_eq!(settings(&h).radio_volume, 0.5);
+    assert_eq!(settings(&h).static_volume, 1.0);
+    assert!(h.has_text("50%"));
+    let mixer = h.app.world().resource::<Mixer>();
+    assert_eq!(mixer.gain(Track::Radio), 0.5);
+    assert_eq!(mixer.gain(Track::Static), 1.0);
+
+    h.click(BACK);
+    h.advance(1);
+    assert_eq!(h.state(), GameState::Paused);
+}
+
+#[test]
+fn sfx_volume_covers_the_tv_and_locks() {
+    let mut h = Harness::new();
+    open_settings(&mut h);
+    h.click(SFX_SLIDER);
+    assert_eq!(settings(&h).sfx_volume, 0.25);
+
+    let mut mixer = h.app.world_mut().resource_mut::<Mixer>();
+    for track in [Track::Sfx, Track::Tv, Track::Ui] {
+        assert_eq!(mixer.gain(track), 0.25, "{:?}", track);
+    }
+    assert_eq!(mixer.gain(Track::Music), 1.0);
+
+    // Muting a track keeps its volume for when it's unmuted.
+    mixer.track_mut(Track::Tv).muted = true;
+    assert_eq!(mixer.gain(Track::Tv), 0.0);
+    mixer.track_mut(Track::Tv).muted = false;
+    assert_eq!(mixer.gain(Track::Tv), 0.25);
+}
+
+#[test]
+fn clicking_the_level_mutes_the_track() {
+    let mut h = Harness::new();
+    open_settings(&mut h);
+    h.click(RADIO_LEVEL);
+    assert!(settings(&h).radio_muted);
+    h.advance(1);
+    assert!(h.has_text("MUTED"));
+    let mixer = h.app.world().resource::<Mixer>();
+    assert_eq!(mixer.gain(Track::Radio), 0.0);
+    assert_eq!(mixer.gain(Track::Static), 1.0);
+
+    h.click(RADIO_LEVEL);
+    assert!(!settings(&h).radio_muted);
+    assert_eq!(h.app.world().resource::<Mixer>().gain(Track::Radio), 1.0);
+}
+
+#[test]
+fn choices_cycle_when_clicked() {
+    let mut h = Harness::new();
+    open_settings(&mut h);
+    assert!(h.has_text("NORMAL"));
+
+    h.click(TEXT_SIZE);
+    assert_eq!(settings(&h).text_size, TextSize::Large);
+    h.click(TEXT_SIZE);
+    h.click(TEXT_SIZE);
+    assert_eq!(settings(&h).text_size, TextSize::Normal);
+
+    h.click(REDUCED_MOTION);
+    assert!(settings(&h).reduced_motion);
+    h.advance(1);
+    assert!(h.has_text("ON"));
+
+    // Escape goes back to the menu the settings were opened from.
+    h.tap_key(KeyCode::Escape);
+    h.advance(1);
+    assert_eq!(h.state(), GameState::Paused);
+    assert!(settings(&h).reduced_motion);
+}
+
+#[test]
+fn settings_survive_a_restart() {
+    let path = std::env::temp_dir().join(format!("settings-{}.ron", std::process::id()));
+    let store = SettingsStore {
+        key: Some(path.to_string_lossy().into_owned()),
+    };
+    assert_eq!(store.load().unwrap(), None);
+
+    let mut saved = Settings {
+        fullscreen: true,
+        text_size: TextSize::Small,
+        ..default()
+    };
+    saved.set_volume(Volume::Music, 0.52);
+    store.save(&saved).unwrap();
+
+    let loaded = store.load().unwrap();
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(loaded, Some(saved));
+    assert_eq!(loaded.unwrap().music_volume, 0.5);
+}
@@ -0,0 +1,55 @@
+use super::Harness;
+use crate::player::ResetUniverse;
+use crate::solutions::{SeedCode, Solutions, BROADCAST_DIGITS};
+
+#[test]
+fn seed_code_round_trips() {
+    for seed in [0, 1, 12345, u32::MAX] {
+        let code = SeedCode(seed).to_string();
+        assert_eq!(SeedCode::parse(&code), Some(SeedCode(seed)));
+        assert_eq!(SeedCode::parse(&code.to_lowercase()), Some(SeedCode(seed)));
+    }
+    assert_eq!(SeedCode::parse("not a code"), None);
+}
+
+#[test]
+fn bad_seed_codes_are_rejected() {
+    assert_eq!(SeedCode::parse(""), None);
+    assert_eq!(SeedCode::parse("   "), None);
+    assert_eq!(SeedCode::parse("ZZZZZZZZZZZZZZ"), None);
+    assert_eq!(SeedCode::parse("00000001"), None);
+    // Seven characters can hold more than 32 bits.
+    assert_eq!(SeedCode::parse("ZZZZZZZ"), None);
+    assert_eq!(SeedCode::parse("A-B"), None);
+}
+
+#[test]
+fn same_seed_same_solutions() {
+    let a = Solutions::generate(SeedCode(42), 3);
+    let b = Solutions::generate(SeedCode(42), 3);
+    assert_eq!(a.keypad_code, b.keypad_code);
+    assert_eq!(a.behind_code, b.behind_code);
+    assert_eq!(a.dial_order, b.dial_order);
+}
+
+#[test]
+fn phone_number_reorders_the_broadcast() {
+    let solutions = Solutions::generate(SeedCode(7), 0);
+    let mut dialed = solutions.phone_number();
+    let mut broadcast = BROADCAST_DIGITS;
+    dialed.sort();
+    broadcast.sort();
+    assert_eq!(dialed, broadcast);
+}
+
+#[test]
+fn keypad_code_survives_reset() {
+    let mut h = Harness::new();
+    let before = h.solutions().clone();
+    h.app.world_mut().send_event(ResetUniverse);
+    h.advance(1);
+    let after = h.solutions();
+    assert_eq!(after.seed, before.seed);
+    assert_eq!(after.universe, before.universe + 1);
+    assert_eq!(after.keypad_code, before.keypad_code);
+}
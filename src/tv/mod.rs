@@ -7,6 +7,9 @@ mod tv_player;
 mod whirlpool;
 
 pub use screen::TvScreenMaterial;
+pub use tv_ending::TvPlayerKilled;
+#[cfg(test)]
+pub use tv_player::TvPlayer;
 
 use crate::{components::UpdateSet, player::ResetUniverse};
 
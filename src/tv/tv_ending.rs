@@ -1,6 +1,9 @@
 use bevy::{prelude::*, render::view::RenderLayers};
 use bevy_kira_audio::prelude::*;
 
+use crate::components::UpdateSet;
+use crate::mixer::TvChannel;
+
 use super::{tv_monster::TvMonster, tv_player::TvPlayer, TvComponent};
 
 #[derive(Event)]
@@ -10,7 +13,7 @@ fn update(
     mut commands: Commands,
     tv_entities: Query<Entity, Or<(With<TvPlayer>, With<TvMonster>)>>,
     mut player_killed: EventReader<TvPlayerKilled>,
-    audio: Res<Audio>,
+    audio: Res<AudioChannel<TvChannel>>,
     asset_server: Res<AssetServer>,
 ) {
     for _ in player_killed.read() {
@@ -34,5 +37,5 @@ fn update(
 
 pub fn tv_ending_plugin(app: &mut App) {
     app.add_event::<TvPlayerKilled>();
-    app.add_systems(Update, update);
+    app.add_systems(Update, update.in_set(UpdateSet::Scene));
 }
@@ -1,5 +1,7 @@
 use bevy::{prelude::*, render::view::RenderLayers};
 
+use crate::components::UpdateSet;
+
 use super::{
     tv_ending::TvPlayerKilled,
     tv_player::{TvControlled, TvPlayer},
@@ -50,6 +52,5 @@ fn update(
 }
 
 pub fn tv_monster_plugin(app: &mut App) {
-    app.add_systems(Update, setup);
-    app.add_systems(Update, update);
+    app.add_systems(Update, (setup, update).in_set(UpdateSet::Scene));
 }
@@ -2,8 +2,12 @@ use bevy::{prelude::*, render::view::RenderLayers};
 use bevy_kira_audio::prelude::*;
 
 use crate::{
+    components::UpdateSet,
+    event_log::GameplayEvent,
     gamedata::SceneId,
+    mixer::TvChannel,
     player::{LightbulbColor, Player, SceneState},
+    progress::Action,
 };
 
 use super::{
@@ -91,7 +95,7 @@ fn update(
     tv_backgrounds: Query<(Entity, &TvBackground)>,
     uncontrolled: Query<Entity, (Or<(With<TvPlayer>, With<TvMonster>)>, Without<TvControlled>)>,
     asset_server: Res<AssetServer>,
-    audio: Res<Audio>,
+    audio: Res<AudioChannel<TvChannel>>,
     player: Res<Player>,
     keyboard: Res<ButtonInput<KeyCode>>,
 ) {
@@ -194,6 +198,7 @@ fn update_tv_player_falling(
     falling_players: Query<&Transform, (With<TvFalling>, With<TvPlayer>)>,
     mut player: ResMut<Player>,
     asset_server: Res<AssetServer>,
+    mut gameplay_events: EventWriter<GameplayEvent>,
 ) {
     for falling_player in falling_players.iter() {
         if falling_player.scale.length() < 0.1 {
@@ -210,7 +215,10 @@ fn update_tv_player_falling(
                 TvComponent,
                 RenderLayers::layer(1),
             ));
-            player.lightbulb_unlock = Some(LightbulbColor::Red);
+            if player.lightbulb_unlock() != Some(LightbulbColor::Red) {
+                gameplay_events.send(GameplayEvent::TvWon(LightbulbColor::Red));
+            }
+            player.flags.perform(Action::WinTv(LightbulbColor::Red));
         }
     }
 }
@@ -220,6 +228,7 @@ fn update_tv_monster_falling(
     falling_players: Query<&Transform, (With<TvFalling>, With<TvMonster>)>,
     mut player: ResMut<Player>,
     asset_server: Res<AssetServer>,
+    mut gameplay_events: EventWriter<GameplayEvent>,
 ) {
     for falling_player in falling_players.iter() {
         if falling_player.scale.length() < 0.1 {
@@ -236,13 +245,16 @@ fn update_tv_monster_falling(
                 TvComponent,
                 RenderLayers::layer(1),
             ));
-            player.lightbulb_unlock = Some(LightbulbColor::Green);
+            if player.lightbulb_unlock() != Some(LightbulbColor::Green) {
+                gameplay_events.send(GameplayEvent::TvWon(LightbulbColor::Green));
+            }
+            player.flags.perform(Action::WinTv(LightbulbColor::Green));
         }
     }
 }
 
 pub fn tv_player_plugin(app: &mut App) {
-    app.add_systems(Update, setup);
+    app.add_systems(Update, setup.in_set(UpdateSet::Scene));
     app.add_systems(
         Update,
         (
@@ -250,6 +262,7 @@ pub fn tv_player_plugin(app: &mut App) {
             update_tv_falling,
             update_tv_player_falling,
             update_tv_monster_falling,
-        ),
+        )
+            .in_set(UpdateSet::Scene),
     );
 }
@@ -1,8 +1,10 @@
 use bevy::prelude::*;
 
+use crate::components::UpdateSet;
+
 #[derive(Component)]
 pub struct Whirlpool {
-    pub speed: f32
+    pub speed: f32,
 }
 
 fn update(mut whirlpools: Query<(&mut Transform, &Whirlpool)>) {
@@ -12,5 +14,5 @@ fn update(mut whirlpools: Query<(&mut Transform, &Whirlpool)>) {
 }
 
 pub fn whirlpool_plugin(app: &mut App) {
-    app.add_systems(Update, update);
+    app.add_systems(Update, update.in_set(UpdateSet::Scene));
 }